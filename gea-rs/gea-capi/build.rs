@@ -0,0 +1,116 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : build.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Generation of the C header of the library, declaring the same
+//  structures and functions as gea-c/gea12.h
+//-----------------------------------------------------------------------------/
+
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// C structure field: declaration and comment.
+type Field = (&'static str, &'static str);
+
+/// C structures exported by the library: name, comment, and fields.
+const STRUCTURES: &[(&str, &str, &[Field])] = &[
+    ("GEAInput", "Algorithms input, has to be initialized by the caller", &[
+        ("uint8_t iv[4];", "32 bits"),
+        ("uint8_t dir;", "1 bit: uplink / downlink"),
+        ("uint8_t key[8];", "64 bits")
+    ]),
+    ("GEAOutput", "Algorithms output, has to be initialized by the caller\n// .len must also be filled in by the caller", &[
+        ("int      len;", "keystream length in bytes"),
+        ("uint8_t* ks;", "keystream, or'ed into the buffer")
+    ]),
+    ("GEA1Ctx", "GEA1 internal state, with the LFSRs as in the C implementation", &[
+        ("uint64_t Sreg;", "S register for initialization, 64 bits"),
+        ("uint64_t Areg;", "A register for keystream generation, 31 bits"),
+        ("uint64_t Breg;", "B register for keystream generation, 32 bits"),
+        ("uint64_t Creg;", "C register for keystream generation, 33 bits")
+    ]),
+    ("GEA2Ctx", "GEA2 internal state, with the LFSRs as in the C implementation", &[
+        ("uint64_t Wreg[2];", "W register for initialization, 97 bits (33 MSB || 64 LSB)"),
+        ("uint64_t Areg;", "A register for keystream generation, 31 bits"),
+        ("uint64_t Breg;", "B register for keystream generation, 32 bits"),
+        ("uint64_t Creg;", "C register for keystream generation, 33 bits"),
+        ("uint64_t Dreg;", "D register for keystream generation, 29 bits")
+    ])
+];
+
+/// Exported functions, per algorithm.
+const FUNCTIONS: &[(&str, &[&str])] = &[
+    ("GEA1", &[
+        "void gea1(GEAInput *in, GEAOutput *out);",
+        "void gea1_init(GEA1Ctx *ctx, GEAInput *in);",
+        "void gea1_gen(GEA1Ctx *ctx, GEAOutput *out);"
+    ]),
+    ("GEA2", &[
+        "void gea2(GEAInput *in, GEAOutput *out);",
+        "void gea2_init(GEA2Ctx *ctx, GEAInput *in);",
+        "void gea2_gen(GEA2Ctx *ctx, GEAOutput *out);"
+    ])
+];
+
+fn header() -> String {
+    let mut header = String::from(
+        "/* Generated by the gea-capi build script: C ABI of gea-rs, compatible\n \
+         * with gea-c/gea12.h. */\n\n\
+         #ifndef GEA12_H\n#define GEA12_H\n\n\
+         #include <stdlib.h>\n#include <stdint.h>\n#include <string.h>\n\n\
+         #ifdef __cplusplus\nextern \"C\" {\n#endif\n\n");
+    for (name, comment, fields) in STRUCTURES {
+        header += &format!("// {}\ntypedef struct {{\n", comment);
+        for (declaration, field_comment) in fields.iter() {
+            header += &format!("    {:<18} // {}\n", declaration, field_comment);
+        }
+        header += &format!("}} {} ;\n\n", name);
+    }
+    header += "// exported function prototypes\n";
+    for (algorithm, prototypes) in FUNCTIONS {
+        header += &format!("// {}\n", algorithm);
+        for prototype in prototypes.iter() {
+            header += &format!("{}\n", prototype);
+        }
+    }
+    header += "\n#ifdef __cplusplus\n}\n#endif\n\n#endif /* GEA12_H */\n";
+    header
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let header = header();
+    fs::write(out_dir.join("gea12.h"), &header).unwrap();
+    
+    // OUT_DIR is target/<profile>/build/<package>/out: also provide the
+    // header next to the libraries
+    if let Some(profile_dir) = out_dir.ancestors().nth(3) {
+        let include_dir = profile_dir.join("include");
+        fs::create_dir_all(&include_dir).unwrap();
+        fs::write(include_dir.join("gea12.h"), &header).unwrap();
+    }
+}
@@ -0,0 +1,311 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : lib.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  C ABI of the GEA1 and GEA2 implementations, with the structures and
+//  functions of the C implementation (gea-c/gea12.h)
+//-----------------------------------------------------------------------------/
+
+
+use std::os::raw::c_int;
+use std::slice;
+
+use gea_rs::gea1::GEA1State;
+use gea_rs::gea2::GEA2State;
+use gea_rs::link_direction::LinkDirection;
+use gea_rs::registers::a_register::ARegister;
+use gea_rs::registers::b_register::BRegister;
+use gea_rs::registers::c_register::CRegister;
+use gea_rs::registers::d_register::DRegister;
+use gea_rs::registers::s_register::SRegister;
+use gea_rs::registers::w_register::WRegister;
+
+/// Algorithms input, initialized by the caller.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct GEAInput {
+    pub iv: [u8; 4],
+    
+    /// Direction bit: 0 for uplink, 1 for downlink.
+    pub dir: u8,
+    
+    pub key: [u8; 8]
+}
+
+/// Algorithms output, initialized by the caller: the keystream is or'ed
+/// into the "len" bytes of the "ks" buffer, which are thus to be zeroed
+/// beforehand.
+#[repr(C)]
+#[derive(Debug)]
+pub struct GEAOutput {
+    pub len: c_int,
+    pub ks: *mut u8
+}
+
+/// GEA1 internal state. The registers are stored as in the C
+/// implementation, whose LFSRs shift the other way round: their bits are
+/// reversed with regard to the registers of gea-rs.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[allow(non_snake_case)]
+pub struct GEA1Ctx {
+    pub Sreg: u64,
+    pub Areg: u64,
+    pub Breg: u64,
+    pub Creg: u64
+}
+
+/// GEA2 internal state, stored as in the C implementation (the W
+/// register being split into its 33 most significant bits and its 64
+/// least significant bits).
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[allow(non_snake_case)]
+pub struct GEA2Ctx {
+    pub Wreg: [u64; 2],
+    pub Areg: u64,
+    pub Breg: u64,
+    pub Creg: u64,
+    pub Dreg: u64
+}
+
+/// Reverse the bits of a register of the given size.
+fn reverse_bits(register: u64, size: usize) -> u64 {
+    register.reverse_bits() >> (64 - size)
+}
+
+impl GEAInput {
+    fn key(&self) -> u64 {
+        u64::from_be_bytes(self.key)
+    }
+    
+    fn iv(&self) -> u32 {
+        u32::from_be_bytes(self.iv)
+    }
+    
+    fn direction(&self) -> LinkDirection {
+        if self.dir & 1 == 0 { LinkDirection::Uplink } else { LinkDirection::Downlink }
+    }
+}
+
+impl GEA1Ctx {
+    fn from_state(state: &GEA1State) -> Self {
+        Self {
+            Sreg: reverse_bits(state.s_register.0 as u64, SRegister::SIZE),
+            Areg: reverse_bits(state.a_register.0, ARegister::SIZE),
+            Breg: reverse_bits(state.b_register.0, BRegister::SIZE),
+            Creg: reverse_bits(state.c_register.0, CRegister::SIZE)
+        }
+    }
+    
+    fn state(&self) -> GEA1State {
+        GEA1State {
+            a_register: ARegister(reverse_bits(self.Areg, ARegister::SIZE)),
+            b_register: BRegister(reverse_bits(self.Breg, BRegister::SIZE)),
+            c_register: CRegister(reverse_bits(self.Creg, CRegister::SIZE)),
+            s_register: SRegister(reverse_bits(self.Sreg, SRegister::SIZE) as u128)
+        }
+    }
+}
+
+impl GEA2Ctx {
+    fn from_state(state: &GEA2State) -> Self {
+        let w_register = state.w_register.0.reverse_bits() >> (128 - WRegister::SIZE);
+        Self {
+            Wreg: [(w_register >> 64) as u64, w_register as u64],
+            Areg: reverse_bits(state.a_register.0, ARegister::SIZE),
+            Breg: reverse_bits(state.b_register.0, BRegister::SIZE),
+            Creg: reverse_bits(state.c_register.0, CRegister::SIZE),
+            Dreg: reverse_bits(state.d_register.0, DRegister::SIZE)
+        }
+    }
+    
+    fn state(&self) -> GEA2State {
+        let w_register = ((self.Wreg[0] as u128) << 64) | self.Wreg[1] as u128;
+        GEA2State {
+            a_register: ARegister(reverse_bits(self.Areg, ARegister::SIZE)),
+            b_register: BRegister(reverse_bits(self.Breg, BRegister::SIZE)),
+            c_register: CRegister(reverse_bits(self.Creg, CRegister::SIZE)),
+            d_register: DRegister(reverse_bits(self.Dreg, DRegister::SIZE)),
+            w_register: WRegister(w_register.reverse_bits() >> (128 - WRegister::SIZE))
+        }
+    }
+}
+
+/// Or the keystream into the output buffer, as the C implementation does.
+///
+/// # Safety
+///
+/// "out.ks" must point to at least "out.len" writable bytes.
+unsafe fn write_keystream(out: &mut GEAOutput, keystream: &[u8]) {
+    let buffer = slice::from_raw_parts_mut(out.ks, keystream.len());
+    for (byte, key_byte) in buffer.iter_mut().zip(keystream) {
+        *byte |= key_byte;
+    }
+}
+
+fn output_length(out: &GEAOutput) -> usize {
+    out.len.max(0) as usize
+}
+
+/// Initialize a GEA1 context from the input.
+///
+/// # Safety
+///
+/// "ctx" and "in" must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn gea1_init(ctx: *mut GEA1Ctx, r#in: *const GEAInput) {
+    let input = &*r#in;
+    let state = GEA1State::initialize(SRegister::initialize(input.key(), input.iv(), input.direction()));
+    *ctx = GEA1Ctx::from_state(&state);
+}
+
+/// Generate "out.len" bytes of keystream from a GEA1 context, which is
+/// updated so that further calls follow on.
+///
+/// # Safety
+///
+/// "ctx" and "out" must be valid pointers, and "out.ks" must point to at
+/// least "out.len" writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn gea1_gen(ctx: *mut GEA1Ctx, out: *mut GEAOutput) {
+    let mut state = (*ctx).state();
+    let keystream = state.generate_stream(output_length(&*out));
+    write_keystream(&mut *out, &keystream);
+    *ctx = GEA1Ctx::from_state(&state);
+}
+
+/// Generate "out.len" bytes of GEA1 keystream from the input.
+///
+/// # Safety
+///
+/// Same requirements as "gea1_init" and "gea1_gen".
+#[no_mangle]
+pub unsafe extern "C" fn gea1(r#in: *const GEAInput, out: *mut GEAOutput) {
+    let mut ctx = GEA1Ctx::default();
+    gea1_init(&mut ctx, r#in);
+    gea1_gen(&mut ctx, out);
+}
+
+/// Initialize a GEA2 context from the input.
+///
+/// # Safety
+///
+/// "ctx" and "in" must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn gea2_init(ctx: *mut GEA2Ctx, r#in: *const GEAInput) {
+    let input = &*r#in;
+    let state = GEA2State::initialize(WRegister::initialize(input.key(), input.iv(), input.direction()));
+    *ctx = GEA2Ctx::from_state(&state);
+}
+
+/// Generate "out.len" bytes of keystream from a GEA2 context, which is
+/// updated so that further calls follow on.
+///
+/// # Safety
+///
+/// "ctx" and "out" must be valid pointers, and "out.ks" must point to at
+/// least "out.len" writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn gea2_gen(ctx: *mut GEA2Ctx, out: *mut GEAOutput) {
+    let mut state = (*ctx).state();
+    let keystream = state.generate_stream(output_length(&*out));
+    write_keystream(&mut *out, &keystream);
+    *ctx = GEA2Ctx::from_state(&state);
+}
+
+/// Generate "out.len" bytes of GEA2 keystream from the input.
+///
+/// # Safety
+///
+/// Same requirements as "gea2_init" and "gea2_gen".
+#[no_mangle]
+pub unsafe extern "C" fn gea2(r#in: *const GEAInput, out: *mut GEAOutput) {
+    let mut ctx = GEA2Ctx::default();
+    gea2_init(&mut ctx, r#in);
+    gea2_gen(&mut ctx, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::{align_of, size_of};
+    use super::*;
+    
+    const INPUT: GEAInput = GEAInput {
+        iv: [0xda, 0x63, 0x7a, 0x83],
+        dir: 1,
+        key: [0x55, 0xe3, 0x03, 0xeb, 0x7d, 0x55, 0xb6, 0x85]
+    };
+    
+    fn keystream(generate: impl FnOnce(*mut GEAOutput), len: usize) -> Vec<u8> {
+        let mut buffer = vec![0; len];
+        let mut out = GEAOutput { len: len as c_int, ks: buffer.as_mut_ptr() };
+        generate(&mut out);
+        buffer
+    }
+    
+    #[test]
+    fn structure_layouts() {
+        assert_eq!((size_of::<GEAInput>(), align_of::<GEAInput>()), (13, 1));
+        assert_eq!(size_of::<GEAOutput>(), 16);
+        assert_eq!(size_of::<GEA1Ctx>(), 32);
+        assert_eq!(size_of::<GEA2Ctx>(), 48);
+    }
+    
+    #[test]
+    fn c_test_vectors() {
+        let plaintext = [0x6e, 0x00, 0xcf, 0xe7, 0xb7, 0xfb, 0x97, 0x48, 0x92, 0xb8, 0xcd, 0xe5, 0xe4, 0x33, 0x63, 0x39, 0x7d, 0x85];
+        let ciphertext: Vec<u8> = keystream(|out| unsafe { gea1(&INPUT, out) }, 18).iter()
+            .zip(plaintext.iter()).map(|(key_byte, byte)| key_byte ^ byte).collect();
+        assert_eq!(ciphertext, [0x58, 0xda, 0xd0, 0x64, 0x57, 0xb9, 0xfe, 0x10, 0x15, 0xda, 0x07, 0x76, 0xed, 0x19, 0x90, 0x7b, 0x78, 0x88]);
+        
+        let input = GEAInput { iv: [0x24, 0xc0, 0x5b, 0x01], dir: 1, key: [0xb1, 0x0f, 0x38, 0x9b, 0x78, 0xa6, 0x16, 0x48] };
+        let plaintext = [0xea, 0xbf, 0x6d, 0x3c, 0x6b, 0xa5, 0xdb, 0xf7, 0x6e, 0xbb, 0x3c, 0x4c, 0x0a, 0xc0, 0x24, 0x0c, 0xb0, 0xab];
+        let ciphertext: Vec<u8> = keystream(|out| unsafe { gea2(&input, out) }, 18).iter()
+            .zip(plaintext.iter()).map(|(key_byte, byte)| key_byte ^ byte).collect();
+        assert_eq!(ciphertext, [0x51, 0x56, 0x56, 0x9d, 0x2a, 0xb9, 0x82, 0x57, 0xbe, 0x1a, 0x37, 0xd6, 0x0d, 0xdf, 0x07, 0xae, 0x90, 0x75]);
+    }
+    
+    #[test]
+    fn context_states() {
+        // Contexts are those of the C implementation, once initialized
+        // and after 7 bytes of keystream, and keystream generated in
+        // several calls follows on
+        let mut ctx = GEA1Ctx::default();
+        unsafe { gea1_init(&mut ctx, &INPUT) };
+        let mut keystream_parts = keystream(|out| unsafe { gea1_gen(&mut ctx, out) }, 7);
+        assert_eq!(ctx, GEA1Ctx { Sreg: 0xb4d509fdc0f807ae, Areg: 0x609053b9, Breg: 0x8c739d60, Creg: 0xbc2cccac });
+        keystream_parts.extend(keystream(|out| unsafe { gea1_gen(&mut ctx, out) }, 11));
+        assert_eq!(keystream_parts, keystream(|out| unsafe { gea1(&INPUT, out) }, 18));
+        
+        let mut ctx = GEA2Ctx::default();
+        unsafe { gea2_init(&mut ctx, &INPUT) };
+        let mut keystream_parts = keystream(|out| unsafe { gea2_gen(&mut ctx, out) }, 7);
+        assert_eq!(ctx, GEA2Ctx {
+            Wreg: [0x1b6f7074, 0x7b6e656859b80ef5], Areg: 0x42dfca8a, Breg: 0x682bab24, Creg: 0x1544d471, Dreg: 0x1128ee51
+        });
+        keystream_parts.extend(keystream(|out| unsafe { gea2_gen(&mut ctx, out) }, 11));
+        assert_eq!(keystream_parts, keystream(|out| unsafe { gea2(&INPUT, out) }, 18));
+    }
+}
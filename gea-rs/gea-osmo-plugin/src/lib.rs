@@ -0,0 +1,207 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : lib.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  GPRS cipher plugin for libosmocore, implementing GEA1 and GEA2 with
+//  the gprs_cipher_impl interface of osmocom/gprs/gprs_cipher.h
+//-----------------------------------------------------------------------------/
+
+
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::ptr;
+use std::slice;
+
+use gea_rs::gea_algorithm::GEAAlgorithm;
+use gea_rs::link_direction::LinkDirection;
+
+// Values of enum gprs_ciph_algo:
+pub const GPRS_ALGO_GEA1: c_int = 1;
+pub const GPRS_ALGO_GEA2: c_int = 2;
+
+// Values of enum gprs_cipher_direction:
+pub const GPRS_CIPH_MS2SGSN: c_int = 0;
+pub const GPRS_CIPH_SGSN2MS: c_int = 1;
+
+/// Maximum length of keystream requested by libosmocore (size of the
+/// largest LLC frame).
+pub const GSM0464_CIPH_MAX_BLOCK: u16 = 1523;
+
+/// Priority of the implementations: libosmocore selects, for each
+/// algorithm, the registered implementation with the lowest value.
+pub const PRIORITY: c_uint = 1;
+
+/// Keystream generation function: fills "len" bytes of "out" from Kc
+/// (its octets most significant first), the GEA input and the direction.
+pub type CipherRun = unsafe extern "C" fn(out: *mut u8, len: u16, kc: *mut u8, iv: u32, direction: c_int) -> c_int;
+
+/// struct llist_head, by which libosmocore links the registered
+/// implementations.
+#[repr(C)]
+pub struct LlistHead {
+    pub next: *mut LlistHead,
+    pub prev: *mut LlistHead
+}
+
+/// struct gprs_cipher_impl.
+#[repr(C)]
+pub struct GprsCipherImpl {
+    pub list: LlistHead,
+    pub algo: c_int,
+    pub name: *const c_char,
+    pub priority: c_uint,
+    pub run: CipherRun
+}
+
+unsafe fn run(algorithm: GEAAlgorithm, out: *mut u8, len: u16, kc: *mut u8, iv: u32, direction: c_int) -> c_int {
+    let direction = match direction {
+        GPRS_CIPH_MS2SGSN => LinkDirection::Uplink,
+        GPRS_CIPH_SGSN2MS => LinkDirection::Downlink,
+        _ => return -1
+    };
+    if out.is_null() || kc.is_null() {
+        return -1;
+    }
+    let mut key = [0; 8];
+    key.copy_from_slice(slice::from_raw_parts(kc, 8));
+    let keystream = match algorithm.generate_stream(u64::from_be_bytes(key).into(), iv, direction, len as usize) {
+        Ok(keystream) => keystream,
+        Err(_) => return -1
+    };
+    slice::from_raw_parts_mut(out, len as usize).copy_from_slice(&keystream);
+    0
+}
+
+unsafe extern "C" fn run_gea1(out: *mut u8, len: u16, kc: *mut u8, iv: u32, direction: c_int) -> c_int {
+    run(GEAAlgorithm::GEA1, out, len, kc, iv, direction)
+}
+
+unsafe extern "C" fn run_gea2(out: *mut u8, len: u16, kc: *mut u8, iv: u32, direction: c_int) -> c_int {
+    run(GEAAlgorithm::GEA2, out, len, kc, iv, direction)
+}
+
+const NAME: *const c_char = b"gea-rs\0".as_ptr() as *const c_char;
+
+/// GEA1 implementation, registered when the plugin is loaded (and also
+/// exported for looking it up directly).
+#[no_mangle]
+pub static mut gea_rs_gea1_impl: GprsCipherImpl = GprsCipherImpl {
+    list: LlistHead { next: ptr::null_mut(), prev: ptr::null_mut() },
+    algo: GPRS_ALGO_GEA1,
+    name: NAME,
+    priority: PRIORITY,
+    run: run_gea1
+};
+
+/// GEA2 implementation.
+#[no_mangle]
+pub static mut gea_rs_gea2_impl: GprsCipherImpl = GprsCipherImpl {
+    list: LlistHead { next: ptr::null_mut(), prev: ptr::null_mut() },
+    algo: GPRS_ALGO_GEA2,
+    name: NAME,
+    priority: PRIORITY,
+    run: run_gea2
+};
+
+/// Pseudo-handle for looking up symbols in the global scope.
+const RTLD_DEFAULT: *mut c_void = ptr::null_mut();
+
+extern "C" {
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+/// Register the implementations with libosmocore, when loaded by
+/// gprs_cipher_load(). gprs_cipher_register() is looked up dynamically,
+/// so that the plugin may also be loaded by other programs.
+extern "C" fn register() {
+    type RegisterFunction = unsafe extern "C" fn(ciph: *mut GprsCipherImpl) -> c_int;
+    unsafe {
+        let function = dlsym(RTLD_DEFAULT, b"gprs_cipher_register\0".as_ptr() as *const c_char);
+        if function.is_null() {
+            return;
+        }
+        let register_cipher: RegisterFunction = std::mem::transmute(function);
+        register_cipher(ptr::addr_of_mut!(gea_rs_gea1_impl));
+        register_cipher(ptr::addr_of_mut!(gea_rs_gea2_impl));
+    }
+}
+
+#[used]
+#[link_section = ".init_array"]
+static REGISTER_ON_LOAD: extern "C" fn() = register;
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::ffi::CString;
+    use std::os::raw::{c_char, c_int, c_void};
+    use std::os::unix::ffi::OsStrExt;
+    use gea_rs::gea1::GEA1State;
+    use gea_rs::gea2::GEA2State;
+    use gea_rs::link_direction::LinkDirection;
+    use gea_rs::registers::s_register::SRegister;
+    use gea_rs::registers::w_register::WRegister;
+    use super::*;
+    
+    const RTLD_NOW: c_int = 2;
+    
+    extern "C" {
+        fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    }
+    
+    /// Load the plugin, built next to the test executable, and look up
+    /// an implementation it exports.
+    fn plugin_implementation(symbol: &[u8]) -> &'static GprsCipherImpl {
+        let path = env::current_exe().unwrap().with_file_name("libgprs_cipher_gea_rs.so");
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        unsafe {
+            let handle = dlopen(path.as_ptr(), RTLD_NOW);
+            assert!(!handle.is_null(), "plugin not found");
+            let implementation = dlsym(handle, symbol.as_ptr() as *const c_char) as *const GprsCipherImpl;
+            assert!(!implementation.is_null());
+            &*implementation
+        }
+    }
+    
+    #[test]
+    fn plugin_keystream() {
+        let mut kc = 0x55e303eb7d55b685u64.to_be_bytes();
+        let mut keystream = vec![0; GSM0464_CIPH_MAX_BLOCK as usize];
+        
+        let gea1 = plugin_implementation(b"gea_rs_gea1_impl\0");
+        assert_eq!((gea1.algo, gea1.priority), (GPRS_ALGO_GEA1, PRIORITY));
+        let result = unsafe { (gea1.run)(keystream.as_mut_ptr(), keystream.len() as u16, kc.as_mut_ptr(), 0xda637a83, GPRS_CIPH_SGSN2MS) };
+        assert_eq!(result, 0);
+        assert_eq!(keystream, GEA1State::initialize(SRegister::initialize(0x55e303eb7d55b685, 0xda637a83, LinkDirection::Downlink))
+            .generate_stream(keystream.len()));
+        
+        let gea2 = plugin_implementation(b"gea_rs_gea2_impl\0");
+        assert_eq!(gea2.algo, GPRS_ALGO_GEA2);
+        let result = unsafe { (gea2.run)(keystream.as_mut_ptr(), 18, kc.as_mut_ptr(), 0xda637a83, GPRS_CIPH_MS2SGSN) };
+        assert_eq!(result, 0);
+        assert_eq!(keystream[..18], GEA2State::initialize(WRegister::initialize(0x55e303eb7d55b685, 0xda637a83, LinkDirection::Uplink))
+            .generate_stream(18)[..]);
+        
+        assert_eq!(unsafe { (gea2.run)(keystream.as_mut_ptr(), 18, kc.as_mut_ptr(), 0, 2) }, -1);
+    }
+}
@@ -0,0 +1,163 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : lib.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Python bindings of GEA1 and GEA2, mirroring the API of gea12.py
+//-----------------------------------------------------------------------------/
+
+
+#[cfg(feature = "numpy")]
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyInt, PyList};
+
+use gea_rs::gea1::GEA1State;
+use gea_rs::gea2::GEA2State;
+use gea_rs::link_direction::LinkDirection;
+use gea_rs::registers::s_register::SRegister;
+use gea_rs::registers::w_register::WRegister;
+
+fn direction(dir: u8) -> LinkDirection {
+    if dir & 1 == 0 { LinkDirection::Uplink } else { LinkDirection::Downlink }
+}
+
+/// Define the Python class of an algorithm: the gen() method of
+/// gea12.py, and faster ones returning bytes or (with the "numpy"
+/// feature) NumPy arrays. Keystream requested in any number of bits or
+/// bytes follows on.
+macro_rules! gea_class {
+    ($class:ident, $name:literal, $state:ident, $register:ident) => {
+        #[pyclass(name = $name)]
+        struct $class {
+            state: $state
+        }
+        
+        #[pymethods]
+        impl $class {
+            /// Initialize the algorithm as gea12.py does, except that the
+            /// key must fit in 64 bits and the IV in 32 bits: wider values
+            /// raise an OverflowError, where gea12.py clocks their extra
+            /// bits into the registers and no longer computes GEA.
+            #[new]
+            fn new(iv: u32, dir: u8, key: u64) -> Self {
+                Self { state: $state::initialize($register::initialize(key, iv, direction(dir))) }
+            }
+            
+            /// Generate "bl" bits of keystream, as a list of bits (the
+            /// last generated first).
+            fn gen<'py>(&mut self, py: Python<'py>, bl: usize) -> PyResult<Bound<'py, PyList>> {
+                PyList::new(py, self.gen_bits(bl))
+            }
+            
+            /// Generate "bl" bits of keystream, as gen() does, into a
+            /// NumPy array of uint8.
+            #[cfg(feature = "numpy")]
+            fn gen_array<'py>(&mut self, py: Python<'py>, bl: usize) -> PyResult<Bound<'py, PyArray1<u8>>> {
+                // Raise an ImportError, rather than panicking, without NumPy
+                py.import("numpy")?;
+                Ok(PyArray1::from_vec(py, self.gen_bits(bl)))
+            }
+            
+            /// Generate keystream bytes, as xor'ed with the LLC data.
+            fn gen_bytes<'py>(&mut self, py: Python<'py>, num_bytes: usize) -> Bound<'py, PyBytes> {
+                PyBytes::new(py, &self.state.generate_stream(num_bytes))
+            }
+            
+            /// Encrypt or decrypt data with the following keystream bytes.
+            fn crypt<'py>(&mut self, py: Python<'py>, data: &[u8]) -> Bound<'py, PyBytes> {
+                PyBytes::new(py, &self.state.crypt_stream(data))
+            }
+        }
+        
+        impl $class {
+            /// Bits in the order returned by gea12.py: the last generated
+            /// first.
+            fn gen_bits(&mut self, num_bits: usize) -> Vec<u8> {
+                let mut bits: Vec<u8> = (0..num_bits).map(|_| self.state.next_bit()).collect();
+                bits.reverse();
+                bits
+            }
+        }
+    };
+}
+
+gea_class!(PyGEA1, "GEA1", GEA1State, SRegister);
+gea_class!(PyGEA2, "GEA2", GEA2State, WRegister);
+
+/// Parent class of the LFSRs in gea12.py, holding the debugging level.
+#[pyclass(name = "LFSR")]
+struct PyLfsr;
+
+#[pymethods]
+impl PyLfsr {
+    #[classattr]
+    fn dbg() -> u8 {
+        0
+    }
+}
+
+/// Convert a big-endian unsigned int of length "bitlen" to a list of bits.
+#[pyfunction]
+fn uint_to_bitlist<'py>(py: Python<'py>, uint: &Bound<'py, PyInt>, bitlen: usize) -> PyResult<Bound<'py, PyList>> {
+    let bit_length: usize = uint.call_method0("bit_length")?.extract()?;
+    let num_bits = bitlen.max(bit_length);
+    let bytes: Vec<u8> = uint.call_method1("to_bytes", (num_bits.div_ceil(8), "big"))?.extract()?;
+    PyList::new(py, (0..num_bits).rev().map(|bit_pos| (bytes[bytes.len() - 1 - bit_pos / 8] >> (bit_pos % 8)) & 1))
+}
+
+/// Convert a list of bits to a big-endian unsigned int.
+#[pyfunction]
+fn bitlist_to_uint<'py>(py: Python<'py>, bl: Vec<u8>) -> PyResult<Bound<'py, PyAny>> {
+    let num_bytes = bl.len().div_ceil(8);
+    let mut bytes = vec![0u8; num_bytes];
+    for (bit_pos, bit) in bl.iter().rev().enumerate() {
+        bytes[num_bytes - 1 - bit_pos / 8] |= (bit & 1) << (bit_pos % 8);
+    }
+    py.get_type::<PyInt>().call_method1("from_bytes", (PyBytes::new(py, &bytes), "big"))
+}
+
+/// Revert the byte order of the "l" least significant bytes of a
+/// big-endian unsigned int (all its bytes by default).
+#[pyfunction]
+#[pyo3(signature = (uint, l=None))]
+fn byte_rev<'py>(py: Python<'py>, uint: &Bound<'py, PyInt>, l: Option<usize>) -> PyResult<Bound<'py, PyAny>> {
+    let l = match l {
+        Some(l) => l,
+        None => uint.call_method0("bit_length")?.extract::<usize>()?.div_ceil(8)
+    };
+    let mask = 1u8.into_pyobject(py)?.call_method1("__lshift__", (8 * l,))?.call_method1("__sub__", (1,))?;
+    let bytes = uint.call_method1("__and__", (mask,))?.call_method1("to_bytes", (l, "little"))?;
+    py.get_type::<PyInt>().call_method1("from_bytes", (bytes, "big"))
+}
+
+#[pymodule]
+fn gea12(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGEA1>()?;
+    module.add_class::<PyGEA2>()?;
+    module.add_class::<PyLfsr>()?;
+    module.add_function(wrap_pyfunction!(uint_to_bitlist, module)?)?;
+    module.add_function(wrap_pyfunction!(bitlist_to_uint, module)?)?;
+    module.add_function(wrap_pyfunction!(byte_rev, module)?)?;
+    Ok(())
+}
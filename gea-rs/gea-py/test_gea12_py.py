@@ -0,0 +1,82 @@
+#!/usr/bin/env python3
+# -*- coding: UTF-8 -*-
+#/**
+# * Software Name : test_gea12_py.py
+# * Version : 0.1
+# *
+# * Copyright 2021. Marin Moulinier. P1Sec.
+# *
+# * This program is free software: you can redistribute it and/or modify
+# * it under the terms of the GNU Affero General Public License as published by
+# * the Free Software Foundation, either version 3 of the License, or
+# * (at your option) any later version.
+# *
+# * This program is distributed in the hope that it will be useful,
+# * but WITHOUT ANY WARRANTY; without even the implied warranty of
+# * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+# * GNU Affero General Public License for more details.
+# *
+# * You should have received a copy of the GNU Affero General Public License
+# * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+# *
+# *--------------------------------------------------------
+# * File Name : test_gea12_py.py
+# * Created : 2026-10-18
+# * Authors : Marin Moulinier
+# *--------------------------------------------------------
+#*/
+
+# Tests of the methods of the gea12 module built from gea-py, which are
+# not provided by gea12.py: gen_bytes(), crypt() and gen_array()
+
+import os
+import unittest
+
+from gea12 import GEA1, GEA2
+
+try:
+    import numpy
+except ImportError:
+    numpy = None
+
+# set by "make test", which builds gea-py with the "numpy" feature: the
+# gen_array() test then fails, instead of being skipped, without NumPy
+REQUIRE_NUMPY = os.environ.get('GEA12_PY_NUMPY') == '1'
+
+KEY, IV, DIR = 0x55e303eb7d55b685, 0xda637a83, 1
+
+
+def bits_to_bytes(bits):
+    """Convert a list of bits returned by gen() into keystream bytes."""
+    bits = bits[::-1]
+    return bytes(sum(bits[8*i + j] << j for j in range(8)) for i in range(len(bits) // 8))
+
+
+class TestGEA12Py(unittest.TestCase):
+
+    def test_gen_bytes(self):
+        for cipher in (GEA1, GEA2):
+            self.assertEqual(cipher(IV, DIR, KEY).gen_bytes(18), bits_to_bytes(cipher(IV, DIR, KEY).gen(144)))
+            # keystream follows on, between bits and bytes
+            gea = cipher(IV, DIR, KEY)
+            gea.gen(3)
+            self.assertEqual(gea.gen_bytes(2), bits_to_bytes(cipher(IV, DIR, KEY).gen(19)[:16]))
+
+    def test_crypt(self):
+        plaintext = bytes.fromhex('58dad06457b9fe1015da0776ed19907b7888')
+        ciphertext = GEA1(IV, DIR, KEY).crypt(plaintext)
+        self.assertEqual(ciphertext, bytes.fromhex('6e00cfe7b7fb974892b8cde5e43363397d85'))
+        self.assertEqual(GEA1(IV, DIR, KEY).crypt(ciphertext), plaintext)
+
+    @unittest.skipUnless(REQUIRE_NUMPY or (numpy is not None and hasattr(GEA1, 'gen_array')),
+                         'requires NumPy, and gea-py built with the "numpy" feature')
+    def test_gen_array(self):
+        self.assertIsNotNone(numpy, 'NumPy is not installed')
+        for cipher in (GEA1, GEA2):
+            array = cipher(IV, DIR, KEY).gen_array(100)
+            self.assertEqual(array.dtype, numpy.uint8)
+            self.assertEqual(array.tolist(), cipher(IV, DIR, KEY).gen(100))
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,128 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : aes.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  AES-128 block encryption, used as the kernel function of Milenage
+//-----------------------------------------------------------------------------/
+
+
+/// Compute the AES S-box: multiplicative inverse in GF(2^8), followed
+/// by the affine transformation.
+const fn compute_sbox() -> [u8; 256] {
+    let mut sbox = [0u8; 256];
+    let mut value = 0;
+    while value < 256 {
+        // Inverse through exponentiation: x^254
+        let mut inverse: u8 = 1;
+        let mut exponent = 0;
+        while exponent < 254 {
+            inverse = multiply(inverse, value as u8);
+            exponent += 1;
+        }
+        let mut output = inverse;
+        let mut rotation = 1;
+        while rotation < 5 {
+            output ^= inverse.rotate_left(rotation);
+            rotation += 1;
+        }
+        sbox[value] = output ^ 0x63;
+        value += 1;
+    }
+    sbox
+}
+
+const SBOX: [u8; 256] = compute_sbox();
+
+/// Multiply two elements of GF(2^8), modulo x^8 + x^4 + x^3 + x + 1.
+const fn multiply(mut left: u8, mut right: u8) -> u8 {
+    let mut product = 0;
+    while right != 0 {
+        if right & 1 != 0 {
+            product ^= left;
+        }
+        left = (left << 1) ^ if left & 0x80 != 0 { 0x1b } else { 0 };
+        right >>= 1;
+    }
+    product
+}
+
+/// AES-128 cipher with its expanded key.
+pub struct Aes128 {
+    round_keys: [[u8; 16]; 11]
+}
+
+impl Aes128 {
+    pub fn new(key: &[u8; 16]) -> Self {
+        let mut round_keys = [[0u8; 16]; 11];
+        round_keys[0] = *key;
+        let mut round_constant = 1;
+        for round in 1..11 {
+            let previous = round_keys[round - 1];
+            let mut word = [SBOX[previous[13] as usize] ^ round_constant, SBOX[previous[14] as usize],
+                SBOX[previous[15] as usize], SBOX[previous[12] as usize]];
+            for column in 0..4 {
+                for row in 0..4 {
+                    word[row] ^= previous[4 * column + row];
+                    round_keys[round][4 * column + row] = word[row];
+                }
+            }
+            round_constant = multiply(round_constant, 2);
+        }
+        Self { round_keys }
+    }
+    
+    pub fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut state = *block;
+        add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1..11 {
+            for byte in state.iter_mut() {
+                *byte = SBOX[*byte as usize];
+            }
+            // ShiftRows, the state being stored column by column
+            let shifted = state;
+            for column in 0..4 {
+                for row in 0..4 {
+                    state[4 * column + row] = shifted[4 * ((column + row) % 4) + row];
+                }
+            }
+            if round != 10 {
+                for column in state.chunks_mut(4) {
+                    let [a, b, c, d] = [column[0], column[1], column[2], column[3]];
+                    column[0] = multiply(a, 2) ^ multiply(b, 3) ^ c ^ d;
+                    column[1] = a ^ multiply(b, 2) ^ multiply(c, 3) ^ d;
+                    column[2] = a ^ b ^ multiply(c, 2) ^ multiply(d, 3);
+                    column[3] = multiply(a, 3) ^ b ^ c ^ multiply(d, 2);
+                }
+            }
+            add_round_key(&mut state, &self.round_keys[round]);
+        }
+        state
+    }
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (byte, key_byte) in state.iter_mut().zip(round_key) {
+        *byte ^= key_byte;
+    }
+}
@@ -0,0 +1,161 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : berlekamp_massey.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Berlekamp-Massey algorithm over GF(2), used to compute the linear
+//  complexity profile of GEA keystreams and register output sequences
+//-----------------------------------------------------------------------------/
+
+
+use crate::lfsr::LinearFeedbackShiftRegister;
+
+/// Result of the Berlekamp-Massey algorithm over a bit sequence.
+pub struct LinearComplexity {
+    /// Coefficients c0 to cL of the shortest connection polynomial
+    /// generating the sequence, c0 (always set) first. The sequence
+    /// verifies s(n) = c1.s(n-1) ^ c2.s(n-2) ^ ... ^ cL.s(n-L).
+    pub connection_polynomial: Vec<bool>,
+    
+    /// Length L of the shortest LFSR generating the whole sequence.
+    pub linear_complexity: usize,
+    
+    /// Linear complexity of each prefix of the sequence: the n-th
+    /// entry is the linear complexity of the n + 1 first bits.
+    pub profile: Vec<usize>
+}
+
+/// Run the Berlekamp-Massey algorithm over a sequence of bits.
+///
+/// Polynomials and the sequence are packed into 64-bit words, so that
+/// the discrepancy and polynomial updates process 64 bits at once. This
+/// keeps the analysis of sequences of a few hundred thousands of bits
+/// practical.
+pub fn berlekamp_massey(sequence: &[bool]) -> LinearComplexity {
+    let num_bits = sequence.len();
+    let num_words = num_bits / 64 + 2;
+    
+    // Bit j of the reversed sequence is s(num_bits - 1 - j), so that the
+    // s(n), s(n-1), ... s(n-L) bits used for computing the n-th discrepancy
+    // are contiguous.
+    let mut reversed: Vec<u64> = vec![0; num_words];
+    for (bit_pos, &bit) in sequence.iter().enumerate() {
+        let rev_pos = num_bits - 1 - bit_pos;
+        reversed[rev_pos / 64] |= (bit as u64) << (rev_pos % 64);
+    }
+    
+    let mut connection: Vec<u64> = vec![0; num_words];
+    let mut previous: Vec<u64> = vec![0; num_words];
+    connection[0] = 1;
+    previous[0] = 1;
+    
+    let mut complexity = 0;
+    let mut previous_complexity = 0;
+    let mut shift = 1;
+    let mut profile = Vec::with_capacity(num_bits);
+    
+    for bit_pos in 0..num_bits {
+        let offset = num_bits - 1 - bit_pos;
+        let mut discrepancy = 0;
+        for (word_pos, word) in connection.iter().enumerate().take(complexity / 64 + 1) {
+            discrepancy ^= word & read_word(&reversed, offset + 64 * word_pos);
+        }
+        
+        if discrepancy.count_ones() & 1 == 0 {
+            shift += 1;
+        }
+        else if 2 * complexity <= bit_pos {
+            let saved_connection = connection.clone();
+            xor_shifted(&mut connection, &previous, previous_complexity, shift);
+            previous = saved_connection;
+            previous_complexity = complexity;
+            complexity = bit_pos + 1 - complexity;
+            shift = 1;
+        }
+        else {
+            xor_shifted(&mut connection, &previous, previous_complexity, shift);
+            shift += 1;
+        }
+        profile.push(complexity);
+    }
+    
+    LinearComplexity {
+        connection_polynomial: (0..=complexity)
+            .map(|coef_pos| (connection[coef_pos / 64] >> (coef_pos % 64)) & 1 != 0)
+            .collect(),
+        linear_complexity: complexity,
+        profile
+    }
+}
+
+/// Unpack a keystream generated by `GEA1State::generate_stream` or
+/// `GEA2State::generate_stream` into its bits, in the order they have
+/// been produced (lowest bit of each byte first).
+pub fn keystream_bits(keystream: &[u8]) -> Vec<bool> {
+    keystream.iter()
+        .flat_map(|byte| (0..8).map(move |num_bit| (byte >> num_bit) & 1 != 0))
+        .collect()
+}
+
+/// Clock an individual register, outputting the result of its "f"
+/// function before each clock, as it is done for contributing to
+/// the keystream.
+pub fn filtered_register_bits<R: LinearFeedbackShiftRegister>(register: &mut R, num_bits: usize) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(num_bits);
+    for _num_bit in 0..num_bits {
+        bits.push(register.f_function());
+        register.clock(None);
+    }
+    bits
+}
+
+/// Read 64 bits of a packed bit vector, starting from an arbitrary
+/// bit position. Bits past the end of the vector are read as zeroes.
+fn read_word(words: &[u64], bit_pos: usize) -> u64 {
+    let word_pos = bit_pos / 64;
+    let bit_shift = bit_pos % 64;
+    let low = words.get(word_pos).copied().unwrap_or(0) >> bit_shift;
+    if bit_shift == 0 {
+        low
+    }
+    else {
+        low | (words.get(word_pos + 1).copied().unwrap_or(0) << (64 - bit_shift))
+    }
+}
+
+/// Xor a polynomial of degree "degree", multiplied by x^shift, into
+/// another polynomial.
+fn xor_shifted(target: &mut [u64], source: &[u64], degree: usize, shift: usize) {
+    let word_shift = shift / 64;
+    let bit_shift = shift % 64;
+    for (word_pos, &word) in source.iter().enumerate().take(degree / 64 + 1) {
+        if let Some(target_word) = target.get_mut(word_pos + word_shift) {
+            *target_word ^= word << bit_shift;
+        }
+        if bit_shift != 0 {
+            if let Some(target_word) = target.get_mut(word_pos + word_shift + 1) {
+                *target_word ^= word >> (64 - bit_shift);
+            }
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : gea.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Command-line tool generating GEA keystream, and encrypting or
+//  decrypting data
+//-----------------------------------------------------------------------------/
+
+
+use std::convert::TryFrom;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use gea_rs::gea_algorithm::GEAAlgorithm;
+use gea_rs::link_direction::LinkDirection;
+
+const USAGE: &str = "\
+Usage:
+    gea keystream --key KEY --iv IV --direction DIR (--length BYTES | --bits BITS) [OPTIONS]
+    gea encrypt --key KEY --iv IV --direction DIR [OPTIONS]
+    gea decrypt --key KEY --iv IV --direction DIR [OPTIONS]
+
+KEY, IV and BITS/BYTES are decimal, or hexadecimal with a 0x prefix. DIR is
+0 or uplink, 1 or downlink.
+
+Options:
+    --algorithm ALGO      gea1 (default), gea2, gea3 or gea4
+    --input FILE          data to encrypt or decrypt (default: stdin)
+    --output FILE         output file (default: stdout)
+    --input-format FMT    raw (default), hex or bits
+    --output-format FMT   raw, hex or bits (default: raw, or hex for a terminal)
+
+The bits format is a Python-style list of bits, ordered as returned by the gen()
+method of gea12.py: the last bit of the keystream (or data) first.";
+
+/// Error reported to the user, with the exit code of the tool.
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Io(io::Error)
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(formatter, "{}\n\n{}", message, USAGE),
+            CliError::Io(error) => write!(formatter, "{}", error)
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Io(error)
+    }
+}
+
+fn usage_error<T>(message: impl Into<String>) -> Result<T, CliError> {
+    Err(CliError::Usage(message.into()))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Format {
+    Raw,
+    Hex,
+    Bits
+}
+
+/// Data to be processed, made of bits in keystream order: the first bit
+/// is the least significant one of the first byte.
+#[derive(Clone, PartialEq, Debug)]
+struct BitData {
+    bytes: Vec<u8>,
+    num_bits: usize
+}
+
+impl BitData {
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        let num_bits = 8 * bytes.len();
+        Self { bytes, num_bits }
+    }
+    
+    fn bit(&self, bit_pos: usize) -> u8 {
+        (self.bytes[bit_pos / 8] >> (bit_pos % 8)) & 1
+    }
+    
+    fn parse(input: &[u8], format: Format) -> Result<Self, CliError> {
+        let text = || String::from_utf8_lossy(input).into_owned();
+        match format {
+            Format::Raw => Ok(Self::from_bytes(input.to_vec())),
+            Format::Hex => {
+                let text = text();
+                let digits: String = text.trim().trim_start_matches("0x").chars()
+                    .filter(|character| !character.is_whitespace()).collect();
+                if digits.len() % 2 != 0 || !digits.chars().all(|character| character.is_ascii_hexdigit()) {
+                    return usage_error("invalid hexadecimal input");
+                }
+                Ok(Self::from_bytes((0..digits.len()).step_by(2)
+                    .map(|position| u8::from_str_radix(&digits[position..position + 2], 16).unwrap()).collect()))
+            },
+            Format::Bits => {
+                let text = text();
+                let list = text.trim().trim_start_matches('[').trim_end_matches(']');
+                let mut bits = vec![];
+                for item in list.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+                    match item {
+                        "0" => bits.push(0),
+                        "1" => bits.push(1),
+                        _ => return usage_error(format!("invalid bit in input: {}", item))
+                    }
+                }
+                bits.reverse();
+                let mut bytes = vec![0; bits.len().div_ceil(8)];
+                for (bit_pos, bit) in bits.iter().enumerate() {
+                    bytes[bit_pos / 8] |= bit << (bit_pos % 8);
+                }
+                Ok(Self { bytes, num_bits: bits.len() })
+            }
+        }
+    }
+    
+    fn format(&self, format: Format) -> Vec<u8> {
+        match format {
+            Format::Raw => self.bytes.clone(),
+            Format::Hex => {
+                let mut text: String = self.bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+                text.push('\n');
+                text.into_bytes()
+            },
+            Format::Bits => {
+                let bits: Vec<String> = (0..self.num_bits).rev().map(|bit_pos| self.bit(bit_pos).to_string()).collect();
+                format!("[{}]\n", bits.join(", ")).into_bytes()
+            }
+        }
+    }
+}
+
+/// Parse a decimal number, or a hexadecimal one with a 0x prefix.
+fn parse_number(name: &str, value: &str) -> Result<u128, CliError> {
+    let value = value.trim();
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(digits) => u128::from_str_radix(&digits.replace(['_', ':'], ""), 16),
+        None => value.replace('_', "").parse()
+    };
+    parsed.or_else(|_| usage_error(format!("invalid {}: {}", name, value)))
+}
+
+fn parse_length(name: &str, value: &str) -> Result<usize, CliError> {
+    usize::try_from(parse_number(name, value)?).or_else(|_| usage_error(format!("{} too large: {}", name, value)))
+}
+
+fn parse_direction(value: &str) -> Result<LinkDirection, CliError> {
+    match value.to_lowercase().as_str() {
+        "0" | "uplink" | "up" => Ok(LinkDirection::Uplink),
+        "1" | "downlink" | "down" => Ok(LinkDirection::Downlink),
+        _ => usage_error(format!("invalid direction: {}", value))
+    }
+}
+
+fn parse_format(value: &str) -> Result<Format, CliError> {
+    match value {
+        "raw" => Ok(Format::Raw),
+        "hex" => Ok(Format::Hex),
+        "bits" => Ok(Format::Bits),
+        _ => usage_error(format!("unknown format: {}", value))
+    }
+}
+
+/// Options common to the subcommands.
+#[derive(Debug)]
+struct Options {
+    algorithm: GEAAlgorithm,
+    key: u128,
+    iv: u32,
+    direction: LinkDirection,
+    length: Option<usize>,
+    num_bits: Option<usize>,
+    input: Option<String>,
+    output: Option<String>,
+    input_format: Format,
+    output_format: Option<Format>
+}
+
+impl Options {
+    fn parse(arguments: &[String]) -> Result<Self, CliError> {
+        let (mut algorithm, mut key, mut iv, mut direction) = (GEAAlgorithm::GEA1, None, None, None);
+        let mut options = Options {
+            algorithm, key: 0, iv: 0, direction: LinkDirection::Uplink, length: None, num_bits: None,
+            input: None, output: None, input_format: Format::Raw, output_format: None
+        };
+        let mut arguments = arguments.iter();
+        while let Some(name) = arguments.next() {
+            let value = match arguments.next() {
+                Some(value) => value,
+                None => return usage_error(format!("missing value for {}", name))
+            };
+            match name.as_str() {
+                "--algorithm" | "-a" => algorithm = match value.to_lowercase().replace('-', "").as_str() {
+                    "gea1" => GEAAlgorithm::GEA1,
+                    "gea2" => GEAAlgorithm::GEA2,
+                    "gea3" => GEAAlgorithm::GEA3,
+                    "gea4" => GEAAlgorithm::GEA4,
+                    _ => return usage_error(format!("unknown algorithm: {}", value))
+                },
+                "--key" | "-k" => key = Some(parse_number("key", value)?),
+                "--iv" | "-i" => iv = Some(parse_number("IV", value)?),
+                "--direction" | "-d" => direction = Some(parse_direction(value)?),
+                "--length" | "-l" => options.length = Some(parse_length("length", value)?),
+                "--bits" | "-b" => options.num_bits = Some(parse_length("number of bits", value)?),
+                "--input" => options.input = Some(value.clone()),
+                "--output" | "-o" => options.output = Some(value.clone()),
+                "--input-format" => options.input_format = parse_format(value)?,
+                "--output-format" => options.output_format = Some(parse_format(value)?),
+                _ => return usage_error(format!("unknown option: {}", name))
+            }
+        }
+        
+        options.algorithm = algorithm;
+        options.key = match key {
+            Some(key) => match algorithm.check_key(key) {
+                Ok(()) => key,
+                Err(error) => return usage_error(error.to_string())
+            },
+            None => return usage_error("missing --key")
+        };
+        options.iv = match iv {
+            Some(iv) if iv > u32::MAX as u128 => return usage_error("the IV is 32 bits long"),
+            Some(iv) => iv as u32,
+            None => return usage_error("missing --iv")
+        };
+        options.direction = match direction {
+            Some(direction) => direction,
+            None => return usage_error("missing --direction")
+        };
+        Ok(options)
+    }
+    
+    fn read_input(&self) -> Result<BitData, CliError> {
+        read_data(self.input.as_deref(), self.input_format)
+    }
+    
+    fn write_output(&self, data: &BitData) -> Result<(), CliError> {
+        let to_stdout = self.output.as_deref().map_or(true, |path| path == "-");
+        // Binary output is not written to a terminal by default
+        let default_format = if to_stdout && is_terminal() { Format::Hex } else { Format::Raw };
+        let output = data.format(self.output_format.unwrap_or(default_format));
+        match &self.output {
+            Some(path) if !to_stdout => fs::write(path, output)?,
+            _ => io::stdout().write_all(&output)?
+        }
+        Ok(())
+    }
+    
+    fn keystream(&self, num_bits: usize) -> Result<BitData, CliError> {
+        let mut bytes = self.algorithm.generate_stream(self.key, self.iv, self.direction, num_bits.div_ceil(8))
+            .map_err(|error| CliError::Usage(error.to_string()))?;
+        if num_bits % 8 != 0 {
+            *bytes.last_mut().unwrap() &= (1 << (num_bits % 8)) - 1;
+        }
+        Ok(BitData { bytes, num_bits })
+    }
+}
+
+/// Read data from a file, or from stdin if the path is missing or "-".
+fn read_data(path: Option<&str>, format: Format) -> Result<BitData, CliError> {
+    let input = match path {
+        Some(path) if path != "-" => fs::read(path)?,
+        _ => {
+            let mut input = vec![];
+            io::stdin().read_to_end(&mut input)?;
+            input
+        }
+    };
+    BitData::parse(&input, format)
+}
+
+fn is_terminal() -> bool {
+    use std::io::IsTerminal;
+    io::stdout().is_terminal()
+}
+
+fn run(arguments: &[String]) -> Result<(), CliError> {
+    let (command, arguments) = match arguments.split_first() {
+        Some((command, arguments)) => (command.as_str(), arguments),
+        None => return usage_error("missing command")
+    };
+    match command {
+        "keystream" => {
+            let options = Options::parse(arguments)?;
+            let num_bits = match (options.length, options.num_bits) {
+                (Some(length), None) => 8 * length,
+                (None, Some(num_bits)) => num_bits,
+                _ => return usage_error("keystream requires either --length or --bits")
+            };
+            options.write_output(&options.keystream(num_bits)?)
+        },
+        "encrypt" | "decrypt" => {
+            let options = Options::parse(arguments)?;
+            let mut data = options.read_input()?;
+            let keystream = options.keystream(data.num_bits)?;
+            for (byte, key_byte) in data.bytes.iter_mut().zip(keystream.bytes) {
+                *byte ^= key_byte;
+            }
+            options.write_output(&data)
+        },
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+        _ => usage_error(format!("unknown command: {}", command))
+    }
+}
+
+fn main() {
+    let arguments: Vec<String> = env::args().skip(1).collect();
+    if let Err(error) = run(&arguments) {
+        eprintln!("gea: {}", error);
+        process::exit(match error {
+            CliError::Usage(_) => 2,
+            CliError::Io(_) => 1
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn data_formats() {
+        let data = BitData::parse(b"1f a1\n", Format::Hex).unwrap();
+        assert_eq!(data, BitData::from_bytes(vec![0x1f, 0xa1]));
+        assert_eq!(data.format(Format::Hex), b"1fa1\n");
+        
+        let bits = BitData::parse(b"[0, 0, 0, 1, 0, 0, 0, 1, 1, 1, 1, 1]", Format::Bits).unwrap();
+        assert_eq!(bits, BitData { bytes: vec![0x1f, 0x01], num_bits: 12 });
+        assert_eq!(bits.format(Format::Bits), b"[0, 0, 0, 1, 0, 0, 0, 1, 1, 1, 1, 1]\n");
+        assert!(BitData::parse(b"[0, 2]", Format::Bits).is_err());
+    }
+    
+    #[test]
+    fn command_options() {
+        let arguments: Vec<String> = "--algorithm gea2 --key 0x55e303eb7d55b685 --iv 3663952515 --direction downlink --bits 12"
+            .split(' ').map(String::from).collect();
+        let options = Options::parse(&arguments).unwrap();
+        assert_eq!((options.algorithm, options.key, options.iv), (GEAAlgorithm::GEA2, 0x55e303eb7d55b685, 0xda637a83));
+        assert_eq!(options.direction, LinkDirection::Downlink);
+        assert_eq!(options.keystream(12).unwrap().num_bits, 12);
+        
+        assert!(Options::parse(&arguments[..4]).is_err());
+        assert!(parse_number("key", "0x1_0000").unwrap() == 0x10000 && parse_number("key", "12a").is_err());
+        assert_eq!(parse_length("length", "0x20").unwrap(), 32);
+        assert!(parse_length("length", &(1u128 << 64).to_string()).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : bitsliced.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Bitsliced implementation of GEA-1 and GEA-2, running 64 instances
+//  (e.g. with different keys or IVs) at once, for cryptanalysis purposes
+//-----------------------------------------------------------------------------/
+
+
+use crate::registers::s_register::SRegister;
+use crate::registers::a_register::ARegister;
+use crate::registers::b_register::BRegister;
+use crate::registers::c_register::CRegister;
+use crate::registers::d_register::DRegister;
+use crate::registers::w_register::WRegister;
+use crate::link_direction::LinkDirection;
+use crate::boolean_function::BooleanFunction;
+use crate::gea_algorithm::LfsrAlgorithm;
+
+/// Inputs of 64 instances of the cipher: the n-th bit of each word
+/// belongs to the n-th instance ("lane").
+pub struct BitslicedInput {
+    pub key: [u64; 64],
+    pub iv: [u64; 32],
+    pub direction: u64
+}
+
+impl BitslicedInput {
+    /// Gather the inputs of up to 64 instances, the n-th one going
+    /// into the n-th lane.
+    pub fn from_instances(instances: &[(u64, u32, LinkDirection)]) -> Self {
+        let mut input = Self { key: [0; 64], iv: [0; 32], direction: 0 };
+        for (lane, &(key, iv, direction)) in instances.iter().enumerate().take(64) {
+            for bit_pos in 0..64 {
+                input.key[bit_pos] |= ((key >> bit_pos) & 1) << lane;
+            }
+            for bit_pos in 0..32 {
+                input.iv[bit_pos] |= (((iv >> bit_pos) & 1) as u64) << lane;
+            }
+            if direction == LinkDirection::Downlink {
+                input.direction |= 1 << lane;
+            }
+        }
+        input
+    }
+    
+    /// Use the same inputs for all the lanes.
+    pub fn broadcast(key: u64, iv: u32, direction: LinkDirection) -> Self {
+        let mut input = Self { key: [0; 64], iv: [0; 32], direction: 0 };
+        for bit_pos in 0..64 {
+            input.key[bit_pos] = 0u64.wrapping_sub((key >> bit_pos) & 1);
+        }
+        for bit_pos in 0..32 {
+            input.iv[bit_pos] = 0u64.wrapping_sub(((iv >> bit_pos) & 1) as u64);
+        }
+        if direction == LinkDirection::Downlink {
+            input.direction = !0;
+        }
+        input
+    }
+}
+
+/// Bitsliced register: the n-th word holds the n-th bit of the register
+/// for each lane.
+struct BitslicedRegister {
+    bits: Vec<u64>,
+    taps: u64,
+    f_inputs: [usize; 7]
+}
+
+impl BitslicedRegister {
+    fn new(size: usize, taps: u64, f_inputs: [usize; 7]) -> Self {
+        Self { bits: vec![0; size], taps, f_inputs }
+    }
+    
+    /// Clock a keystream generation register (A, B, C or D).
+    fn clock(&mut self, input: u64) {
+        let feedback = self.bits.remove(0) ^ input;
+        for (bit_pos, bit) in self.bits.iter_mut().enumerate() {
+            if (self.taps >> bit_pos) & 1 != 0 {
+                *bit ^= feedback;
+            }
+        }
+        self.bits.push(feedback);
+    }
+    
+    /// Clock an initialization register (S or W), whose feedback
+    /// goes through the "f" function.
+    fn clock_nonlinear(&mut self, input: u64, f_monomials: &[usize]) {
+        let f_output = self.f_function(f_monomials);
+        let feedback = self.bits.remove(0) ^ f_output ^ input;
+        self.bits.push(feedback);
+    }
+    
+    fn f_function(&self, f_monomials: &[usize]) -> u64 {
+        let inputs: Vec<u64> = self.f_inputs.iter().map(|&bit_pos| self.bits[bit_pos]).collect();
+        f_monomials.iter()
+            .map(|&monomial| inputs.iter().enumerate()
+                .filter(|(var_pos, _)| (monomial >> var_pos) & 1 != 0)
+                .fold(!0, |product, (_, input)| product & input))
+            .fold(0, |output, product| output ^ product)
+    }
+    
+    /// Replace the registers which are zero by one, as done when
+    /// initializing the A, B, C and D registers.
+    fn fix_zero_lanes(&mut self) {
+        let non_zero_lanes = self.bits.iter().fold(0, |lanes, bit| lanes | bit);
+        self.bits[0] |= !non_zero_lanes;
+    }
+}
+
+/// Bitsliced GEA-1 or GEA-2 state.
+pub struct BitslicedState {
+    pub algorithm: LfsrAlgorithm,
+    f_monomials: Vec<usize>,
+    registers: Vec<BitslicedRegister>
+}
+
+impl BitslicedState {
+    /// Initialize 64 instances of the cipher, with a given number of
+    /// mixing clocks (the actual algorithms using `SRegister::MIXING_CLOCKS`
+    /// or `WRegister::MIXING_CLOCKS`).
+    pub fn initialize(algorithm: LfsrAlgorithm, input: &BitslicedInput, mixing_clocks: usize) -> Self {
+        let f_anf = BooleanFunction::gea_f_function().algebraic_normal_form();
+        let f_monomials: Vec<usize> = (0..f_anf.len()).filter(|&monomial| f_anf[monomial]).collect();
+        
+        let mut init_register = match algorithm {
+            LfsrAlgorithm::GEA1 => BitslicedRegister::new(SRegister::SIZE, 0, SRegister::F_INPUTS),
+            LfsrAlgorithm::GEA2 => BitslicedRegister::new(WRegister::SIZE, 0, WRegister::F_INPUTS),
+        };
+        for &input_word in input.iv.iter().chain(std::iter::once(&input.direction)).chain(input.key.iter()) {
+            init_register.clock_nonlinear(input_word, &f_monomials);
+        }
+        for _num_clock in 0..mixing_clocks {
+            init_register.clock_nonlinear(0, &f_monomials);
+        }
+        
+        // Load the A, B, C (and D) registers in the same way as
+        // `GEA1State::initialize` and `GEA2State::initialize`
+        let mut registers = vec![
+            BitslicedRegister::new(ARegister::SIZE, ARegister::TAPS, ARegister::F_INPUTS),
+            BitslicedRegister::new(BRegister::SIZE, BRegister::TAPS, BRegister::F_INPUTS),
+            BitslicedRegister::new(CRegister::SIZE, CRegister::TAPS, CRegister::F_INPUTS)
+        ];
+        let rotations: &[usize] = match algorithm {
+            LfsrAlgorithm::GEA1 => &[0, 16, 32],
+            LfsrAlgorithm::GEA2 => {
+                registers.push(BitslicedRegister::new(DRegister::SIZE, DRegister::TAPS, DRegister::F_INPUTS));
+                &[16, 33, 51, 0]
+            },
+        };
+        
+        let init_size = init_register.bits.len();
+        for (register, rotation) in registers.iter_mut().zip(rotations) {
+            for input_pos in 0..init_size {
+                register.clock(init_register.bits[(input_pos + rotation) % init_size]);
+            }
+            register.fix_zero_lanes();
+        }
+        
+        Self { algorithm, f_monomials, registers }
+    }
+    
+    /// Output the next keystream bit of each lane, then clock the
+    /// registers.
+    pub fn next_keystream_word(&mut self) -> u64 {
+        let mut output = 0;
+        for register in self.registers.iter_mut() {
+            output ^= register.f_function(&self.f_monomials);
+            register.clock(0);
+        }
+        output
+    }
+    
+    /// Output the next keystream bits of each lane, in the order they
+    /// are output (lowest bit of each keystream byte first).
+    pub fn generate_words(&mut self, num_bits: usize) -> Vec<u64> {
+        (0..num_bits).map(|_| self.next_keystream_word()).collect()
+    }
+    
+    /// Extract the keystream bytes of a given lane from the output of
+    /// `generate_words`.
+    pub fn lane_keystream(words: &[u64], lane: usize) -> Vec<u8> {
+        words.chunks(8)
+            .map(|byte_words| byte_words.iter().enumerate()
+                .fold(0, |byte, (num_bit, word)| byte | ((((word >> lane) & 1) as u8) << num_bit)))
+            .collect()
+    }
+}
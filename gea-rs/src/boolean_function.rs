@@ -0,0 +1,271 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : boolean_function.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Analysis of the 7-input boolean "f" function used by GEA-1 and GEA-2
+//  (or of any research variant of it): algebraic normal form, Walsh-Hadamard
+//  spectrum, nonlinearity, correlation immunity and annihilators
+//-----------------------------------------------------------------------------/
+
+
+use crate::f_lookup_table::F_LOOKUP_TABLE;
+
+/// Number of inputs of the "f" function.
+pub const NUM_INPUTS: usize = 7;
+
+/// Size of the truth table of a 7-input boolean function.
+pub const TABLE_SIZE: usize = 1 << NUM_INPUTS;
+
+/// A boolean function of 7 inputs, represented by its truth table.
+///
+/// The n-th entry of the truth table is the output of the function when
+/// its input bits x0 to x6 are the bits of n, x0 being the lowest one,
+/// which is the same ordering as `F_LOOKUP_TABLE`.
+///
+/// Polynomials in algebraic normal form are represented with the same
+/// kind of table: the n-th entry is the coefficient of the monomial made
+/// of the variables whose index is set in n (entry 0 being the constant).
+pub struct BooleanFunction {
+    pub truth_table: [bool; TABLE_SIZE]
+}
+
+impl BooleanFunction {
+    /// Create the "f" function used by GEA-1 and GEA-2.
+    pub fn gea_f_function() -> Self {
+        Self { truth_table: F_LOOKUP_TABLE }
+    }
+    
+    /// Create a function from an arbitrary truth table.
+    pub fn from_truth_table(truth_table: [bool; TABLE_SIZE]) -> Self {
+        Self { truth_table }
+    }
+    
+    /// Create a function from the coefficients of its algebraic
+    /// normal form.
+    pub fn from_algebraic_normal_form(anf: [bool; TABLE_SIZE]) -> Self {
+        // The Moebius transform is an involution
+        Self { truth_table: moebius_transform(anf) }
+    }
+    
+    /// Return the 1 + f function.
+    pub fn complement(&self) -> Self {
+        let mut truth_table = self.truth_table;
+        for output in truth_table.iter_mut() {
+            *output = !*output;
+        }
+        Self { truth_table }
+    }
+    
+    /// Compute the coefficients of the algebraic normal form of the
+    /// function, through the Moebius transform of its truth table.
+    pub fn algebraic_normal_form(&self) -> [bool; TABLE_SIZE] {
+        moebius_transform(self.truth_table)
+    }
+    
+    /// Degree of the largest monomial of the algebraic normal form
+    /// (0 for a constant function).
+    pub fn algebraic_degree(&self) -> usize {
+        polynomial_degree(&self.algebraic_normal_form())
+    }
+    
+    /// Compute the Walsh-Hadamard spectrum of the function, the n-th
+    /// entry being the sum over x of (-1)^(f(x) ^ n.x)
+    pub fn walsh_hadamard_spectrum(&self) -> [i32; TABLE_SIZE] {
+        let mut spectrum = [0; TABLE_SIZE];
+        for (input, &output) in self.truth_table.iter().enumerate() {
+            spectrum[input] = if output { -1 } else { 1 };
+        }
+        
+        let mut half_size = 1;
+        while half_size < TABLE_SIZE {
+            for block_pos in (0..TABLE_SIZE).step_by(2 * half_size) {
+                for pos in block_pos..block_pos + half_size {
+                    let (low, high) = (spectrum[pos], spectrum[pos + half_size]);
+                    spectrum[pos] = low + high;
+                    spectrum[pos + half_size] = low - high;
+                }
+            }
+            half_size *= 2;
+        }
+        spectrum
+    }
+    
+    /// Whether the function outputs as many zeroes as ones.
+    pub fn is_balanced(&self) -> bool {
+        self.walsh_hadamard_spectrum()[0] == 0
+    }
+    
+    /// Hamming distance between the function and the set of affine
+    /// functions.
+    pub fn nonlinearity(&self) -> u32 {
+        let max_walsh = self.walsh_hadamard_spectrum().iter()
+            .map(|coefficient| coefficient.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        (TABLE_SIZE as u32 - max_walsh) / 2
+    }
+    
+    /// Largest order m such that the output of the function is
+    /// statistically independent from any set of m input bits, i.e.
+    /// such that the Walsh-Hadamard spectrum is zero for all the
+    /// non-zero masks of weight up to m.
+    pub fn correlation_immunity(&self) -> usize {
+        let spectrum = self.walsh_hadamard_spectrum();
+        (1..=NUM_INPUTS)
+            .take_while(|&order| (1..TABLE_SIZE)
+                .filter(|mask| mask.count_ones() as usize == order)
+                .all(|mask| spectrum[mask] == 0))
+            .count()
+    }
+    
+    /// Compute a basis of the annihilators of the function with an
+    /// algebraic degree up to "max_degree", i.e. of the functions g
+    /// such that f.g = 0. Annihilators are returned in algebraic
+    /// normal form.
+    ///
+    /// The annihilators of 1 + f can be obtained through calling this
+    /// on `complement()`.
+    pub fn annihilators(&self, max_degree: usize) -> Vec<[bool; TABLE_SIZE]> {
+        let monomials: Vec<usize> = (0..TABLE_SIZE)
+            .filter(|monomial| monomial.count_ones() as usize <= max_degree)
+            .collect();
+        
+        // g(x) must be zero for each input x for which f(x) is one, which
+        // gives one linear equation over the coefficients of g per input.
+        let equations: Vec<u128> = (0..TABLE_SIZE)
+            .filter(|&input| self.truth_table[input])
+            .map(|input| monomials.iter().enumerate()
+                .filter(|(_, &monomial)| monomial & input == monomial)
+                .fold(0, |equation, (column, _)| equation | (1 << column)))
+            .collect();
+        
+        null_space(equations, monomials.len()).into_iter()
+            .map(|solution| {
+                let mut anf = [false; TABLE_SIZE];
+                for (column, &monomial) in monomials.iter().enumerate() {
+                    anf[monomial] = (solution >> column) & 1 != 0;
+                }
+                anf
+            })
+            .collect()
+    }
+    
+    /// Smallest degree of a non-zero annihilator of f or 1 + f.
+    pub fn algebraic_immunity(&self) -> usize {
+        let complement = self.complement();
+        (0..=NUM_INPUTS)
+            .find(|&degree| !self.annihilators(degree).is_empty() ||
+                !complement.annihilators(degree).is_empty())
+            .unwrap_or(NUM_INPUTS)
+    }
+}
+
+/// Degree of the largest monomial of a polynomial in algebraic
+/// normal form (0 for a constant).
+pub fn polynomial_degree(anf: &[bool; TABLE_SIZE]) -> usize {
+    (0..TABLE_SIZE)
+        .filter(|&monomial| anf[monomial])
+        .map(|monomial| monomial.count_ones() as usize)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Format a polynomial in algebraic normal form the same way as it is
+/// written in the "generate_f_lookup_table.py" script, e.g.
+/// "x0*x2*x5 ^ x1 ^ 1", from the highest degree monomials to the lowest.
+pub fn format_algebraic_normal_form(anf: &[bool; TABLE_SIZE]) -> String {
+    let mut monomials: Vec<Vec<usize>> = (0..TABLE_SIZE)
+        .filter(|&monomial| anf[monomial])
+        .map(|monomial| (0..NUM_INPUTS).filter(|var_pos| (monomial >> var_pos) & 1 != 0).collect())
+        .collect();
+    monomials.sort_by(|left, right| right.len().cmp(&left.len()).then(left.cmp(right)));
+    
+    if monomials.is_empty() {
+        return "0".to_string();
+    }
+    monomials.iter()
+        .map(|variables| {
+            if variables.is_empty() {
+                "1".to_string()
+            }
+            else {
+                variables.iter()
+                    .map(|var_pos| format!("x{}", var_pos))
+                    .collect::<Vec<String>>()
+                    .join("*")
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ^ ")
+}
+
+/// Convert a truth table into algebraic normal form coefficients,
+/// or conversely.
+fn moebius_transform(mut table: [bool; TABLE_SIZE]) -> [bool; TABLE_SIZE] {
+    for var_pos in 0..NUM_INPUTS {
+        for input in 0..TABLE_SIZE {
+            if (input >> var_pos) & 1 != 0 {
+                table[input] ^= table[input ^ (1 << var_pos)];
+            }
+        }
+    }
+    table
+}
+
+/// Compute a basis of the solutions of an homogeneous system of linear
+/// equations over GF(2), each equation being a bit mask over (at most
+/// 128) unknowns.
+fn null_space(mut equations: Vec<u128>, num_unknowns: usize) -> Vec<u128> {
+    // Reduce the system to its row echelon form
+    let mut pivots: Vec<usize> = vec![];
+    for column in 0..num_unknowns {
+        let row_pos = pivots.len();
+        let pivot_row = match (row_pos..equations.len()).find(|&row| (equations[row] >> column) & 1 != 0) {
+            Some(pivot_row) => pivot_row,
+            None => continue
+        };
+        equations.swap(row_pos, pivot_row);
+        let pivot_equation = equations[row_pos];
+        for (row, equation) in equations.iter_mut().enumerate() {
+            if row != row_pos && (*equation >> column) & 1 != 0 {
+                *equation ^= pivot_equation;
+            }
+        }
+        pivots.push(column);
+    }
+    
+    // Each unknown which is not a pivot can be freely chosen
+    (0..num_unknowns)
+        .filter(|column| !pivots.contains(column))
+        .map(|free_column| {
+            let mut solution: u128 = 1 << free_column;
+            for (row, &pivot_column) in pivots.iter().enumerate() {
+                if (equations[row] >> free_column) & 1 != 0 {
+                    solution |= 1 << pivot_column;
+                }
+            }
+            solution
+        })
+        .collect()
+}
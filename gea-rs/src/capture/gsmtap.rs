@@ -0,0 +1,332 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : gsmtap.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  GSMTAP encapsulation of GPRS frames in captured UDP packets, and
+//  deciphering of the LLC frames of capture files
+//-----------------------------------------------------------------------------/
+
+
+use std::ops::Range;
+use std::path::Path;
+
+use crate::link_direction::LinkDirection;
+use crate::gea_algorithm::GEAAlgorithm;
+use crate::llc::frame::LlcControl;
+use crate::llc::session::LlcSession;
+use crate::rlc_mac::{GprsCodingScheme, LlcReassembler, ReassembledLlcPdu, RlcDataBlock};
+use crate::capture::pcap::{CaptureError, CaptureFile, LINKTYPE_NULL, LINKTYPE_ETHERNET,
+    LINKTYPE_RAW, LINKTYPE_LINUX_SLL, LINKTYPE_IPV4, LINKTYPE_IPV6, LINKTYPE_LINUX_SLL2};
+
+/// UDP port of GSMTAP.
+pub const GSMTAP_UDP_PORT: u16 = 4729;
+
+/// GSMTAP payload types.
+pub const GSMTAP_TYPE_UM: u8 = 0x01;
+pub const GSMTAP_TYPE_GB_LLC: u8 = 0x08;
+pub const GSMTAP_TYPE_GB_SNDCP: u8 = 0x09;
+
+/// GSMTAP channel type of Um packet data channels.
+pub const GSMTAP_CHANNEL_PDCH: u8 = 0x0d;
+
+/// Flag of the ARFCN field set for uplink frames.
+const GSMTAP_ARFCN_UPLINK: u16 = 0x4000;
+
+/// Header of a GSMTAP packet.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GsmtapHeader {
+    pub version: u8,
+    pub payload_type: u8,
+    pub timeslot: u8,
+    
+    /// ARFCN, including its PCS and uplink flags.
+    pub arfcn: u16,
+    
+    pub signal_dbm: i8,
+    pub snr_db: i8,
+    pub frame_number: u32,
+    
+    /// Channel type, for Um payloads.
+    pub sub_type: u8,
+    
+    pub antenna: u8,
+    pub sub_slot: u8
+}
+
+impl GsmtapHeader {
+    pub fn direction(&self) -> LinkDirection {
+        if self.arfcn & GSMTAP_ARFCN_UPLINK != 0 { LinkDirection::Uplink } else { LinkDirection::Downlink }
+    }
+}
+
+/// GSMTAP packet located within a captured packet.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GsmtapPacket {
+    pub header: GsmtapHeader,
+    
+    /// Location of the GSMTAP payload within the captured packet.
+    pub payload: Range<usize>,
+    
+    /// Location of the IP header and UDP datagram, for updating the
+    /// UDP checksum once the payload has been modified.
+    ip_header: Range<usize>,
+    udp: Range<usize>
+}
+
+impl GsmtapPacket {
+    /// Locate the GSMTAP header and payload of a captured packet, going
+    /// through its link, IP (v4 or v6) and UDP layers.
+    pub fn locate(link_type: u32, packet: &[u8]) -> Option<Self> {
+        let mut ip_start = match link_type {
+            LINKTYPE_NULL => 4,
+            LINKTYPE_ETHERNET => {
+                let mut ethertype_position = 12;
+                while read_u16(packet, ethertype_position)? == 0x8100 {
+                    ethertype_position += 4;
+                }
+                ethertype_position + 2
+            },
+            LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => 0,
+            LINKTYPE_LINUX_SLL => 16,
+            LINKTYPE_LINUX_SLL2 => 20,
+            _ => return None
+        };
+        
+        let (ip_header, udp_start) = match packet.get(ip_start)? >> 4 {
+            4 => {
+                let header_length = (packet[ip_start] & 0x0f) as usize * 4;
+                if header_length < 20 {
+                    return None;
+                }
+                let fragmented = read_u16(packet, ip_start + 6)? & 0x3fff != 0;
+                if *packet.get(ip_start + 9)? != 17 || fragmented {
+                    return None;
+                }
+                (ip_start..ip_start + header_length, ip_start + header_length)
+            },
+            6 => {
+                if *packet.get(ip_start + 6)? != 17 {
+                    return None;
+                }
+                (ip_start..ip_start + 40, ip_start + 40)
+            },
+            _ => return None
+        };
+        ip_start = udp_start;
+        
+        let source_port = read_u16(packet, ip_start)?;
+        let destination_port = read_u16(packet, ip_start + 2)?;
+        let udp_length = read_u16(packet, ip_start + 4)? as usize;
+        if (source_port != GSMTAP_UDP_PORT && destination_port != GSMTAP_UDP_PORT) ||
+            udp_length < 8 || udp_start + udp_length > packet.len() {
+            return None;
+        }
+        let udp = udp_start..udp_start + udp_length;
+        
+        let gsmtap = &packet[udp.start + 8..udp.end];
+        if gsmtap.len() < 16 || !(1..=2).contains(&gsmtap[0]) {
+            return None;
+        }
+        let header_length = gsmtap[1] as usize * 4;
+        if header_length < 16 || header_length > gsmtap.len() {
+            return None;
+        }
+        let header = GsmtapHeader {
+            version: gsmtap[0],
+            payload_type: gsmtap[2],
+            timeslot: gsmtap[3],
+            arfcn: u16::from_be_bytes([gsmtap[4], gsmtap[5]]),
+            signal_dbm: gsmtap[6] as i8,
+            snr_db: gsmtap[7] as i8,
+            frame_number: u32::from_be_bytes([gsmtap[8], gsmtap[9], gsmtap[10], gsmtap[11]]),
+            sub_type: gsmtap[12],
+            antenna: gsmtap[13],
+            sub_slot: gsmtap[14]
+        };
+        Some(Self { header, payload: udp.start + 8 + header_length..udp.end, ip_header, udp })
+    }
+    
+    /// Update the UDP checksum of the captured packet after its payload
+    /// has been modified (unless it was not computed, over IPv4).
+    pub fn update_udp_checksum(&self, packet: &mut [u8]) {
+        let checksum_position = self.udp.start + 6;
+        let ip_header = &packet[self.ip_header.clone()];
+        let mut pseudo_header = vec![];
+        if ip_header[0] >> 4 == 4 {
+            if packet[checksum_position..checksum_position + 2] == [0, 0] {
+                return;
+            }
+            pseudo_header.extend_from_slice(&ip_header[12..20]);
+        } else {
+            pseudo_header.extend_from_slice(&ip_header[8..40]);
+        }
+        pseudo_header.extend_from_slice(&[0, 17]);
+        pseudo_header.extend_from_slice(&(self.udp.len() as u16).to_be_bytes());
+        
+        packet[checksum_position] = 0;
+        packet[checksum_position + 1] = 0;
+        let sum = internet_checksum_sum(&pseudo_header) + internet_checksum_sum(&packet[self.udp.clone()]);
+        let mut checksum = !fold_checksum(sum);
+        if checksum == 0 {
+            checksum = 0xffff;
+        }
+        packet[checksum_position..checksum_position + 2].copy_from_slice(&checksum.to_be_bytes());
+    }
+}
+
+/// Outcome of deciphering the LLC frames of a capture.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct DecryptionSummary {
+    /// Number of GSMTAP packets carrying LLC frames.
+    pub llc_frames: usize,
+    
+    /// Number of ciphered frames deciphered with a valid FCS.
+    pub deciphered_frames: usize,
+    
+    /// Number of ciphered frames which could not be deciphered (e.g.
+    /// with an invalid FCS because of a wrong key or IOV).
+    pub invalid_frames: usize
+}
+
+/// Decipher the LLC frames carried by the GSMTAP packets of a capture,
+/// in place. Deciphered UI frames have their E bit cleared (and their
+/// FCS updated accordingly) so that dissectors handle them as plaintext.
+///
+/// GSMTAP does not carry the TLLI of LLC frames, so they are all
+/// attributed to the logical link of the given TLLI within the session.
+pub fn decrypt_capture(capture: &mut CaptureFile, session: &mut LlcSession, tlli: u32) -> DecryptionSummary {
+    let mut summary = DecryptionSummary::default();
+    for packet_index in 0..capture.packets.len() {
+        let link_type = capture.packets[packet_index].link_type;
+        let packet = capture.packet_data_mut(packet_index);
+        let gsmtap = match GsmtapPacket::locate(link_type, packet) {
+            Some(gsmtap) if gsmtap.header.payload_type == GSMTAP_TYPE_GB_LLC => gsmtap,
+            _ => continue
+        };
+        summary.llc_frames += 1;
+        
+        let data = &packet[gsmtap.payload.clone()];
+        let ciphering = match session.process_frame(tlli, gsmtap.header.direction(), data) {
+            Ok(ciphering) if ciphering.parameters.is_some() => ciphering,
+            _ => continue
+        };
+        let mut frame = match ciphering.decrypt(data) {
+            Ok(frame) => frame,
+            Err(_) => {
+                summary.invalid_frames += 1;
+                continue;
+            }
+        };
+        if let LlcControl::Unconfirmed { ref mut encrypted, .. } = frame.control {
+            *encrypted = false;
+        }
+        packet[gsmtap.payload.clone()].copy_from_slice(&frame.serialize());
+        gsmtap.update_udp_checksum(packet);
+        summary.deciphered_frames += 1;
+    }
+    summary
+}
+
+/// Decipher the LLC frames of a pcap or pcapng file with the given
+/// algorithm and Kc, writing a capture file of the same format.
+pub fn decrypt_capture_file<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q, algorithm: GEAAlgorithm, kc: u128)
+        -> Result<DecryptionSummary, CaptureError> {
+    let mut capture = CaptureFile::read(input)?;
+    let summary = decrypt_capture(&mut capture, &mut LlcSession::new(algorithm, kc), 0);
+    capture.write(output)?;
+    Ok(summary)
+}
+
+/// Reassemble the LLC PDUs carried by the GPRS and EGPRS RLC/MAC data
+/// blocks of the GSMTAP Um packets of a capture, in capture order. Both
+/// are told apart from the block length, 33-octet blocks being decoded as
+/// GPRS CS-2 rather than EGPRS MCS-2. They may then
+/// be deciphered within an LLC session, using their TLLI if known.
+pub fn rlc_llc_pdus(capture: &CaptureFile) -> Vec<ReassembledLlcPdu> {
+    let mut reassembler = LlcReassembler::new();
+    let mut pdus = vec![];
+    for (packet_index, packet) in capture.packets.iter().enumerate() {
+        let data = capture.packet_data(packet_index);
+        let gsmtap = match GsmtapPacket::locate(packet.link_type, data) {
+            Some(gsmtap) if gsmtap.header.payload_type == GSMTAP_TYPE_UM &&
+                gsmtap.header.sub_type & 0x7f == GSMTAP_CHANNEL_PDCH => gsmtap,
+            _ => continue
+        };
+        let (direction, payload) = (gsmtap.header.direction(), &data[gsmtap.payload]);
+        let blocks = match GprsCodingScheme::from_block_length(payload.len()) {
+            Some(_) => RlcDataBlock::parse_gprs(direction, payload).map(|block| vec![block]),
+            None => RlcDataBlock::parse_egprs_block(direction, payload)
+        };
+        // Control blocks, split blocks, and blocks with other coding
+        // schemes, are skipped
+        for block in blocks.unwrap_or_default() {
+            if let Ok(block_pdus) = reassembler.push_block(block) {
+                pdus.extend(block_pdus);
+            }
+        }
+    }
+    pdus
+}
+
+/// Encapsulate a GSMTAP payload in a UDP/IPv4 packet, as it would be
+/// captured with the raw IP link type (no checksum is computed).
+pub fn encapsulate(header: &GsmtapHeader, payload: &[u8]) -> Vec<u8> {
+    let udp_length = 8 + 16 + payload.len();
+    let mut packet = vec![0x45, 0x00];
+    packet.extend_from_slice(&(20 + udp_length as u16).to_be_bytes());
+    packet.extend_from_slice(&[0, 0, 0x40, 0x00, 64, 17, 0, 0, 127, 0, 0, 1, 127, 0, 0, 1]);
+    let ip_checksum = !fold_checksum(internet_checksum_sum(&packet));
+    packet[10..12].copy_from_slice(&ip_checksum.to_be_bytes());
+    
+    packet.extend_from_slice(&GSMTAP_UDP_PORT.to_be_bytes());
+    packet.extend_from_slice(&GSMTAP_UDP_PORT.to_be_bytes());
+    packet.extend_from_slice(&(udp_length as u16).to_be_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    
+    packet.extend_from_slice(&[header.version, 4, header.payload_type, header.timeslot]);
+    packet.extend_from_slice(&header.arfcn.to_be_bytes());
+    packet.extend_from_slice(&[header.signal_dbm as u8, header.snr_db as u8]);
+    packet.extend_from_slice(&header.frame_number.to_be_bytes());
+    packet.extend_from_slice(&[header.sub_type, header.antenna, header.sub_slot, 0]);
+    packet.extend_from_slice(payload);
+    packet
+}
+
+fn read_u16(packet: &[u8], position: usize) -> Option<u16> {
+    let bytes = packet.get(position..position + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn internet_checksum_sum(data: &[u8]) -> u32 {
+    data.chunks(2)
+        .map(|word| ((word[0] as u32) << 8) | *word.get(1).unwrap_or(&0) as u32)
+        .sum()
+}
+
+fn fold_checksum(mut sum: u32) -> u16 {
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    sum as u16
+}
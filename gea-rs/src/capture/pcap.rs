@@ -0,0 +1,255 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : pcap.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Reading of pcap and pcapng capture files, locating the data of each
+//  captured packet so that it can be modified in place
+//-----------------------------------------------------------------------------/
+
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// Link types of captured packets used with GSMTAP.
+pub const LINKTYPE_NULL: u32 = 0;
+pub const LINKTYPE_ETHERNET: u32 = 1;
+pub const LINKTYPE_RAW: u32 = 101;
+pub const LINKTYPE_LINUX_SLL: u32 = 113;
+pub const LINKTYPE_IPV4: u32 = 228;
+pub const LINKTYPE_IPV6: u32 = 229;
+pub const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+/// Magic numbers of pcap files (with microsecond or nanosecond
+/// timestamps), and of pcapng section header blocks.
+const PCAP_MAGIC: u32 = 0xa1b2c3d4;
+const PCAP_NANOSECOND_MAGIC: u32 = 0xa1b23c4d;
+const PCAPNG_SECTION_HEADER: u32 = 0x0a0d0d0a;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b3c4d;
+
+/// pcapng block types containing packets or their interface.
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+const PCAPNG_SIMPLE_PACKET: u32 = 3;
+const PCAPNG_ENHANCED_PACKET: u32 = 6;
+
+/// Error encountered when reading or writing a capture file.
+#[derive(Debug)]
+pub enum CaptureError {
+    Io(io::Error),
+    
+    /// The file is neither a pcap nor a pcapng file.
+    UnknownFormat,
+    
+    /// A header, block or packet is truncated.
+    Truncated,
+    
+    /// A pcapng packet refers to an undescribed interface.
+    UnknownInterface
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaptureError::Io(error) => write!(formatter, "capture file I/O error: {}", error),
+            CaptureError::UnknownFormat => formatter.write_str("unknown capture file format"),
+            CaptureError::Truncated => formatter.write_str("truncated capture file"),
+            CaptureError::UnknownInterface => formatter.write_str("packet captured on an unknown interface")
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl From<io::Error> for CaptureError {
+    fn from(error: io::Error) -> Self {
+        CaptureError::Io(error)
+    }
+}
+
+/// Format of a capture file.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CaptureFormat {
+    Pcap,
+    Pcapng
+}
+
+/// Packet within a capture file.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CapturedPacket {
+    pub link_type: u32,
+    
+    /// Location of the captured packet data within the file.
+    pub data: Range<usize>
+}
+
+/// Capture file loaded in memory. Packets keep their location in the
+/// file, so that their contents can be modified (without changing their
+/// length) and the file written back with everything else unchanged.
+pub struct CaptureFile {
+    pub format: CaptureFormat,
+    pub packets: Vec<CapturedPacket>,
+    contents: Vec<u8>
+}
+
+impl CaptureFile {
+    /// Parse the contents of a pcap or pcapng file.
+    pub fn parse(contents: Vec<u8>) -> Result<Self, CaptureError> {
+        let magic = read_u32(&contents, 0, false)?;
+        let (format, packets) = if magic == PCAPNG_SECTION_HEADER {
+            (CaptureFormat::Pcapng, parse_pcapng(&contents)?)
+        } else {
+            (CaptureFormat::Pcap, parse_pcap(&contents)?)
+        };
+        Ok(Self { format, packets, contents })
+    }
+    
+    /// Create an empty pcap file (little endian, with microsecond
+    /// timestamps), whose packets have the given link type.
+    pub fn new_pcap(link_type: u32) -> Self {
+        let mut contents = PCAP_MAGIC.to_le_bytes().to_vec();
+        contents.extend_from_slice(&[2, 0, 4, 0]); // Version 2.4
+        contents.extend_from_slice(&[0; 8]);
+        contents.extend_from_slice(&0xffffu32.to_le_bytes()); // Snapshot length
+        contents.extend_from_slice(&link_type.to_le_bytes());
+        Self { format: CaptureFormat::Pcap, packets: vec![], contents }
+    }
+    
+    /// Append a packet to a file created with "new_pcap".
+    pub fn push_packet(&mut self, timestamp_microseconds: u64, data: &[u8]) {
+        assert_eq!(self.format, CaptureFormat::Pcap);
+        let link_type = u32::from_le_bytes([self.contents[20], self.contents[21], self.contents[22], self.contents[23]]);
+        self.contents.extend_from_slice(&((timestamp_microseconds / 1_000_000) as u32).to_le_bytes());
+        self.contents.extend_from_slice(&((timestamp_microseconds % 1_000_000) as u32).to_le_bytes());
+        self.contents.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        self.contents.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        let start = self.contents.len();
+        self.contents.extend_from_slice(data);
+        self.packets.push(CapturedPacket { link_type, data: start..self.contents.len() });
+    }
+    
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, CaptureError> {
+        Self::parse(fs::read(path)?)
+    }
+    
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), CaptureError> {
+        Ok(fs::write(path, &self.contents)?)
+    }
+    
+    /// Contents of the whole file.
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+    
+    /// Data of a captured packet.
+    pub fn packet_data(&self, packet_index: usize) -> &[u8] {
+        &self.contents[self.packets[packet_index].data.clone()]
+    }
+    
+    pub fn packet_data_mut(&mut self, packet_index: usize) -> &mut [u8] {
+        let range = self.packets[packet_index].data.clone();
+        &mut self.contents[range]
+    }
+}
+
+fn parse_pcap(contents: &[u8]) -> Result<Vec<CapturedPacket>, CaptureError> {
+    let big_endian = match read_u32(contents, 0, true)? {
+        PCAP_MAGIC | PCAP_NANOSECOND_MAGIC => true,
+        _ => match read_u32(contents, 0, false)? {
+            PCAP_MAGIC | PCAP_NANOSECOND_MAGIC => false,
+            _ => return Err(CaptureError::UnknownFormat)
+        }
+    };
+    let link_type = read_u32(contents, 20, big_endian)? & 0x0fffffff;
+    
+    let mut packets = vec![];
+    let mut position = 24;
+    while position < contents.len() {
+        let captured_length = read_u32(contents, position + 8, big_endian)? as usize;
+        let data = position + 16..position + 16 + captured_length;
+        if data.end > contents.len() {
+            return Err(CaptureError::Truncated);
+        }
+        position = data.end;
+        packets.push(CapturedPacket { link_type, data });
+    }
+    Ok(packets)
+}
+
+fn parse_pcapng(contents: &[u8]) -> Result<Vec<CapturedPacket>, CaptureError> {
+    let mut packets = vec![];
+    let mut big_endian = false;
+    let mut interface_link_types: Vec<u32> = vec![];
+    let mut position = 0;
+    while position < contents.len() {
+        let block_type = read_u32(contents, position, big_endian)?;
+        if block_type == PCAPNG_SECTION_HEADER {
+            big_endian = read_u32(contents, position + 8, true)? == PCAPNG_BYTE_ORDER_MAGIC;
+            interface_link_types.clear();
+        }
+        let block_length = read_u32(contents, position + 4, big_endian)? as usize;
+        let block = position..position + block_length;
+        if block_length < 12 || block.end > contents.len() {
+            return Err(CaptureError::Truncated);
+        }
+        
+        match block_type {
+            PCAPNG_INTERFACE_DESCRIPTION => {
+                interface_link_types.push(read_u16(contents, position + 8, big_endian)? as u32);
+            },
+            PCAPNG_ENHANCED_PACKET => {
+                let interface = read_u32(contents, position + 8, big_endian)? as usize;
+                let link_type = *interface_link_types.get(interface).ok_or(CaptureError::UnknownInterface)?;
+                let captured_length = read_u32(contents, position + 20, big_endian)? as usize;
+                let data = position + 28..position + 28 + captured_length;
+                if data.end > block.end {
+                    return Err(CaptureError::Truncated);
+                }
+                packets.push(CapturedPacket { link_type, data });
+            },
+            PCAPNG_SIMPLE_PACKET => {
+                let link_type = *interface_link_types.first().ok_or(CaptureError::UnknownInterface)?;
+                let original_length = read_u32(contents, position + 8, big_endian)? as usize;
+                let data = position + 12..(position + 12 + original_length).min(block.end - 4);
+                packets.push(CapturedPacket { link_type, data });
+            },
+            _ => {}
+        }
+        position = block.end;
+    }
+    Ok(packets)
+}
+
+fn read_u16(contents: &[u8], position: usize, big_endian: bool) -> Result<u16, CaptureError> {
+    let bytes = contents.get(position..position + 2).ok_or(CaptureError::Truncated)?;
+    let bytes = [bytes[0], bytes[1]];
+    Ok(if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+}
+
+fn read_u32(contents: &[u8], position: usize, big_endian: bool) -> Result<u32, CaptureError> {
+    let bytes = contents.get(position..position + 4).ok_or(CaptureError::Truncated)?;
+    let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    Ok(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+}
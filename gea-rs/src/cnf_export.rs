@@ -0,0 +1,463 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : cnf_export.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Export of the GEA-1 and GEA-2 keystream generation as a DIMACS CNF
+//  formula, for running SAT-based attacks over partially guessed states
+//-----------------------------------------------------------------------------/
+
+
+use crate::registers::s_register::SRegister;
+use crate::registers::a_register::ARegister;
+use crate::registers::b_register::BRegister;
+use crate::registers::c_register::CRegister;
+use crate::registers::d_register::DRegister;
+use crate::registers::w_register::WRegister;
+use crate::f_lookup_table::F_LOOKUP_TABLE;
+use crate::gea1::GEA1State;
+use crate::gea2::GEA2State;
+
+/// Selects the cipher to be encoded, and which part of its internal
+/// state is represented by the variables of the formula.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CnfModel {
+    /// GEA-1 keystream generation from the A, B and C registers,
+    /// encoded with 31 + 32 + 33 variables.
+    GEA1Registers,
+    
+    /// GEA-1 keystream generation from the S register, once it has
+    /// been initialized from the key, IV and direction bit, encoded
+    /// with 64 variables.
+    GEA1SRegister,
+    
+    /// GEA-2 keystream generation from the A, B, C and D registers,
+    /// encoded with 31 + 32 + 33 + 29 variables.
+    GEA2Registers,
+    
+    /// GEA-2 keystream generation from the W register, once it has
+    /// been initialized from the key, IV and direction bit, encoded
+    /// with 97 variables.
+    GEA2WRegister
+}
+
+/// Designates a register whose bits are represented by the state
+/// variables of a formula.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RegisterName {
+    A,
+    B,
+    C,
+    D,
+    S,
+    W
+}
+
+impl CnfModel {
+    /// Registers (and their sizes) whose bits are the state variables,
+    /// in the order of their numbering.
+    pub fn state_registers(&self) -> &'static [(RegisterName, usize)] {
+        match self {
+            CnfModel::GEA1Registers => &[
+                (RegisterName::A, ARegister::SIZE),
+                (RegisterName::B, BRegister::SIZE),
+                (RegisterName::C, CRegister::SIZE)
+            ],
+            CnfModel::GEA1SRegister => &[(RegisterName::S, SRegister::SIZE)],
+            CnfModel::GEA2Registers => &[
+                (RegisterName::A, ARegister::SIZE),
+                (RegisterName::B, BRegister::SIZE),
+                (RegisterName::C, CRegister::SIZE),
+                (RegisterName::D, DRegister::SIZE)
+            ],
+            CnfModel::GEA2WRegister => &[(RegisterName::W, WRegister::SIZE)]
+        }
+    }
+    
+    /// Number of state variables, which are numbered from 1 in the
+    /// DIMACS output.
+    pub fn num_state_variables(&self) -> usize {
+        self.state_registers().iter().map(|(_, size)| size).sum()
+    }
+    
+    /// DIMACS variable representing a given bit of a state register,
+    /// if this register is part of the model.
+    pub fn state_variable(&self, register: RegisterName, bit_pos: usize) -> Option<usize> {
+        let mut first_variable = 1;
+        for &(state_register, size) in self.state_registers() {
+            if state_register == register {
+                return if bit_pos < size { Some(first_variable + bit_pos) } else { None };
+            }
+            first_variable += size;
+        }
+        None
+    }
+}
+
+/// Register whose bits are linear combinations of the state variables,
+/// represented as bit masks (bit n standing for the variable n + 1).
+///
+/// As long as they are used for keystream generation, the A, B, C and D
+/// registers are linear, and the transformation from the S or W register
+/// to their initial state is linear as well (except for the zero register
+/// being replaced by one, which is ignored here).
+struct LinearRegister {
+    bits: Vec<u128>,
+    taps: u64,
+    f_inputs: [usize; 7]
+}
+
+impl LinearRegister {
+    fn from_variables(first_variable_bit: usize, size: usize, taps: u64, f_inputs: [usize; 7]) -> Self {
+        Self {
+            bits: (0..size).map(|bit_pos| 1 << (first_variable_bit + bit_pos)).collect(),
+            taps,
+            f_inputs
+        }
+    }
+    
+    /// Load the register from the initialized S or W register, whose bits
+    /// are rotated by "rotation" positions, the same way the
+    /// `GEA1State::initialize` and `GEA2State::initialize` functions do.
+    fn from_init_register(init_size: usize, rotation: usize, size: usize, taps: u64, f_inputs: [usize; 7]) -> Self {
+        let mut register = Self { bits: vec![0; size], taps, f_inputs };
+        for input_pos in 0..init_size {
+            register.clock(1 << ((input_pos + rotation) % init_size));
+        }
+        register
+    }
+    
+    fn clock(&mut self, input: u128) {
+        let feedback = self.bits.remove(0) ^ input;
+        self.bits.push(feedback);
+        for (bit_pos, bit) in self.bits.iter_mut().enumerate() {
+            if (self.taps >> bit_pos) & 1 != 0 {
+                *bit ^= feedback;
+            }
+        }
+    }
+}
+
+/// Build a formula describing the keystream generation of GEA-1 or GEA-2,
+/// and output it in the DIMACS CNF format.
+///
+/// Variables 1 to `CnfModel::num_state_variables()` are the state
+/// variables, further ones are auxiliary variables standing for the
+/// inputs and output of the "f" function at each clock.
+pub struct CnfEncoder {
+    model: CnfModel,
+    xor_clauses: bool,
+    num_variables: usize,
+    clauses: Vec<Vec<isize>>,
+    xors: Vec<(Vec<usize>, bool)>,
+    initial_registers: Vec<Vec<u128>>,
+    registers: Vec<LinearRegister>,
+    current_clock: usize
+}
+
+impl CnfEncoder {
+    /// Create a new encoder. When "xor_clauses" is set, xor constraints
+    /// are output as CryptoMiniSat "x" clauses, otherwise they are
+    /// expanded into plain CNF clauses.
+    pub fn new(model: CnfModel, xor_clauses: bool) -> Self {
+        let registers = match model {
+            CnfModel::GEA1Registers | CnfModel::GEA2Registers => {
+                let mut registers = vec![
+                    LinearRegister::from_variables(0, ARegister::SIZE, ARegister::TAPS, ARegister::F_INPUTS),
+                    LinearRegister::from_variables(ARegister::SIZE, BRegister::SIZE, BRegister::TAPS, BRegister::F_INPUTS),
+                    LinearRegister::from_variables(ARegister::SIZE + BRegister::SIZE, CRegister::SIZE, CRegister::TAPS, CRegister::F_INPUTS)
+                ];
+                if model == CnfModel::GEA2Registers {
+                    registers.push(LinearRegister::from_variables(
+                        ARegister::SIZE + BRegister::SIZE + CRegister::SIZE, DRegister::SIZE, DRegister::TAPS, DRegister::F_INPUTS));
+                }
+                registers
+            },
+            CnfModel::GEA1SRegister => vec![
+                LinearRegister::from_init_register(SRegister::SIZE, 0, ARegister::SIZE, ARegister::TAPS, ARegister::F_INPUTS),
+                LinearRegister::from_init_register(SRegister::SIZE, 16, BRegister::SIZE, BRegister::TAPS, BRegister::F_INPUTS),
+                LinearRegister::from_init_register(SRegister::SIZE, 32, CRegister::SIZE, CRegister::TAPS, CRegister::F_INPUTS)
+            ],
+            CnfModel::GEA2WRegister => vec![
+                LinearRegister::from_init_register(WRegister::SIZE, 16, ARegister::SIZE, ARegister::TAPS, ARegister::F_INPUTS),
+                LinearRegister::from_init_register(WRegister::SIZE, 33, BRegister::SIZE, BRegister::TAPS, BRegister::F_INPUTS),
+                LinearRegister::from_init_register(WRegister::SIZE, 51, CRegister::SIZE, CRegister::TAPS, CRegister::F_INPUTS),
+                LinearRegister::from_init_register(WRegister::SIZE, 0, DRegister::SIZE, DRegister::TAPS, DRegister::F_INPUTS)
+            ]
+        };
+        
+        Self {
+            model,
+            xor_clauses,
+            num_variables: model.num_state_variables(),
+            clauses: vec![],
+            xors: vec![],
+            initial_registers: registers.iter().map(|register| register.bits.clone()).collect(),
+            registers,
+            current_clock: 0
+        }
+    }
+    
+    pub fn model(&self) -> CnfModel {
+        self.model
+    }
+    
+    pub fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+    
+    /// Fix the value of a variable, e.g. a guessed state bit.
+    pub fn fix_variable(&mut self, variable: usize, value: bool) {
+        self.clauses.push(vec![if value { variable as isize } else { -(variable as isize) }]);
+    }
+    
+    /// Fix the value of a bit of a state register. Returns false if this
+    /// register is not represented by the model.
+    pub fn fix_state_bit(&mut self, register: RegisterName, bit_pos: usize, value: bool) -> bool {
+        match self.model.state_variable(register, bit_pos) {
+            Some(variable) => {
+                self.fix_variable(variable, value);
+                true
+            },
+            None => false
+        }
+    }
+    
+    /// Add a known keystream bit, output at the given clock (the first
+    /// keystream bit being output at clock 0, and the n-th keystream byte
+    /// being made of the bits from clock 8 * n, lowest bit first).
+    pub fn add_keystream_bit(&mut self, clock: usize, value: bool) {
+        if clock < self.current_clock {
+            for (register, initial_bits) in self.registers.iter_mut().zip(self.initial_registers.iter()) {
+                register.bits = initial_bits.clone();
+            }
+            self.current_clock = 0;
+        }
+        while self.current_clock < clock {
+            for register in self.registers.iter_mut() {
+                register.clock(0);
+            }
+            self.current_clock += 1;
+        }
+        
+        let f_inputs: Vec<Vec<u128>> = self.registers.iter()
+            .map(|register| register.f_inputs.iter().map(|&bit_pos| register.bits[bit_pos]).collect())
+            .collect();
+        let f_outputs: Vec<usize> = f_inputs.iter()
+            .map(|inputs| self.add_f_function(inputs))
+            .collect();
+        self.xors.push((f_outputs, value));
+    }
+    
+    /// Add known keystream bits, from a given clock.
+    pub fn add_keystream_bits(&mut self, first_clock: usize, bits: &[bool]) {
+        for (bit_pos, &bit) in bits.iter().enumerate() {
+            self.add_keystream_bit(first_clock + bit_pos, bit);
+        }
+    }
+    
+    /// Add known keystream bytes, as output by the `generate_stream`
+    /// functions.
+    pub fn add_keystream(&mut self, keystream: &[u8]) {
+        for (byte_pos, byte) in keystream.iter().enumerate() {
+            for num_bit in 0..8 {
+                self.add_keystream_bit(8 * byte_pos + num_bit, (byte >> num_bit) & 1 != 0);
+            }
+        }
+    }
+    
+    /// Output the formula in the DIMACS CNF format.
+    pub fn to_dimacs(&self) -> String {
+        let mut num_variables = self.num_variables;
+        let mut clauses = self.clauses.clone();
+        let mut xor_lines: Vec<String> = vec![];
+        
+        for (variables, value) in &self.xors {
+            if self.xor_clauses && !variables.is_empty() {
+                let mut literals: Vec<isize> = variables.iter().map(|&variable| variable as isize).collect();
+                if !value {
+                    literals[0] = -literals[0];
+                }
+                xor_lines.push(format!("x{} 0", join_literals(&literals)));
+            }
+            else {
+                expand_xor(variables, *value, &mut num_variables, &mut clauses);
+            }
+        }
+        
+        let mut output = format!("c GEA CNF model: {:?}\n", self.model);
+        output += &format!("c state variables: 1 to {}\n", self.model.num_state_variables());
+        output += &format!("p cnf {} {}\n", num_variables, clauses.len() + xor_lines.len());
+        for clause in &clauses {
+            output += &format!("{} 0\n", join_literals(clause));
+        }
+        for xor_line in &xor_lines {
+            output += xor_line;
+            output.push('\n');
+        }
+        output
+    }
+    
+    fn new_variable(&mut self) -> usize {
+        self.num_variables += 1;
+        self.num_variables
+    }
+    
+    /// Add a variable standing for a linear combination of the state
+    /// variables, or directly use the state variable when there is a
+    /// single one.
+    fn add_linear_combination(&mut self, combination: u128) -> usize {
+        if combination.count_ones() == 1 {
+            return combination.trailing_zeros() as usize + 1;
+        }
+        let variable = self.new_variable();
+        let mut variables: Vec<usize> = (0..128)
+            .filter(|bit_pos| (combination >> bit_pos) & 1 != 0)
+            .map(|bit_pos| bit_pos + 1)
+            .collect();
+        variables.push(variable);
+        self.xors.push((variables, false));
+        variable
+    }
+    
+    /// Add a variable standing for the output of the "f" function, and
+    /// the clauses linking it to its inputs: one per line of the truth
+    /// table.
+    fn add_f_function(&mut self, inputs: &[u128]) -> usize {
+        let input_variables: Vec<usize> = inputs.iter()
+            .map(|&combination| self.add_linear_combination(combination))
+            .collect();
+        let output_variable = self.new_variable();
+        
+        for (input, &output) in F_LOOKUP_TABLE.iter().enumerate() {
+            let mut clause: Vec<isize> = input_variables.iter().enumerate()
+                .map(|(var_pos, &variable)| {
+                    if (input >> var_pos) & 1 != 0 { -(variable as isize) } else { variable as isize }
+                })
+                .collect();
+            clause.push(if output { output_variable as isize } else { -(output_variable as isize) });
+            self.clauses.push(clause);
+        }
+        output_variable
+    }
+}
+
+/// Expand a xor constraint into plain clauses, cutting it into chunks
+/// linked by auxiliary variables in order to keep the clause count low.
+fn expand_xor(variables: &[usize], value: bool, num_variables: &mut usize, clauses: &mut Vec<Vec<isize>>) {
+    let mut variables = variables.to_vec();
+    while variables.len() > 4 {
+        *num_variables += 1;
+        let chunk_variable = *num_variables;
+        let mut chunk: Vec<usize> = variables.drain(..3).collect();
+        chunk.push(chunk_variable);
+        expand_short_xor(&chunk, false, clauses);
+        variables.push(chunk_variable);
+    }
+    expand_short_xor(&variables, value, clauses);
+}
+
+/// Expand a xor constraint into the clauses forbidding each assignment
+/// with the wrong parity.
+fn expand_short_xor(variables: &[usize], value: bool, clauses: &mut Vec<Vec<isize>>) {
+    for assignment in 0..(1 << variables.len()) {
+        if ((assignment as u32).count_ones() & 1 != 0) != value {
+            clauses.push(variables.iter().enumerate()
+                .map(|(var_pos, &variable)| {
+                    if (assignment >> var_pos) & 1 != 0 { -(variable as isize) } else { variable as isize }
+                })
+                .collect());
+        }
+    }
+}
+
+fn join_literals(literals: &[isize]) -> String {
+    literals.iter().map(|literal| literal.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+/// Internal state recovered from the model output by a SAT solver.
+pub enum DecodedState {
+    GEA1Registers(ARegister, BRegister, CRegister),
+    GEA1SRegister(SRegister),
+    GEA2Registers(ARegister, BRegister, CRegister, DRegister),
+    GEA2WRegister(WRegister)
+}
+
+impl DecodedState {
+    /// Generate keystream from the recovered state, e.g. to verify it
+    /// against further known keystream.
+    pub fn generate_stream(self, num_bytes: usize) -> Vec<u8> {
+        match self {
+            DecodedState::GEA1Registers(a_register, b_register, c_register) => GEA1State {
+                a_register, b_register, c_register, s_register: SRegister(0)
+            }.generate_stream(num_bytes),
+            DecodedState::GEA1SRegister(s_register) => GEA1State::initialize(s_register).generate_stream(num_bytes),
+            DecodedState::GEA2Registers(a_register, b_register, c_register, d_register) => GEA2State {
+                a_register, b_register, c_register, d_register, w_register: WRegister(0)
+            }.generate_stream(num_bytes),
+            DecodedState::GEA2WRegister(w_register) => GEA2State::initialize(w_register).generate_stream(num_bytes)
+        }
+    }
+}
+
+/// Map the model output by a SAT solver (either "v" lines from a solver
+/// in the SAT competition format, or a bare list of literals) back to
+/// register states. Returns None if a state variable is not assigned.
+pub fn decode_model(model: CnfModel, solver_output: &str) -> Option<DecodedState> {
+    let mut assignment: Vec<Option<bool>> = vec![None; model.num_state_variables() + 1];
+    for line in solver_output.lines() {
+        let line = line.trim();
+        let literals = match line.strip_prefix('v') {
+            Some(literals) => literals,
+            None if line.starts_with(|character: char| character == '-' || character.is_ascii_digit()) => line,
+            None => continue
+        };
+        for literal in literals.split_whitespace() {
+            let literal: isize = literal.parse().ok()?;
+            if let Some(value) = assignment.get_mut(literal.unsigned_abs()) {
+                *value = Some(literal > 0);
+            }
+        }
+    }
+    
+    let mut registers: Vec<u128> = vec![];
+    let mut variable = 1;
+    for &(_, size) in model.state_registers() {
+        let mut register = 0;
+        for bit_pos in 0..size {
+            register |= (assignment[variable]? as u128) << bit_pos;
+            variable += 1;
+        }
+        registers.push(register);
+    }
+    
+    Some(match model {
+        CnfModel::GEA1Registers => DecodedState::GEA1Registers(
+            ARegister(registers[0] as u64), BRegister(registers[1] as u64), CRegister(registers[2] as u64)),
+        CnfModel::GEA1SRegister => DecodedState::GEA1SRegister(SRegister(registers[0])),
+        CnfModel::GEA2Registers => DecodedState::GEA2Registers(
+            ARegister(registers[0] as u64), BRegister(registers[1] as u64),
+            CRegister(registers[2] as u64), DRegister(registers[3] as u64)),
+        CnfModel::GEA2WRegister => DecodedState::GEA2WRegister(WRegister(registers[0]))
+    })
+}
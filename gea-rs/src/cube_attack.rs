@@ -0,0 +1,234 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : cube_attack.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Cube attack tooling over the IV bits of GEA-1 and GEA-2 with a reduced
+//  number of mixing clocks: cube summation and detection of linear
+//  superpolys in the key bits
+//
+//  No linear superpoly was found for GEA-1 or GEA-2, even without any
+//  mixing clock: the IV bits are loaded before the key bits, which go
+//  through the nonlinear feedback of the S (or W) register on top of them.
+//  Searching random cubes of 1 to 20 IV bits over the first 64 keystream
+//  bits gave no superpoly passing the linearity tests.
+//-----------------------------------------------------------------------------/
+
+
+use std::fmt;
+
+use crate::link_direction::LinkDirection;
+use crate::gea_algorithm::LfsrAlgorithm;
+use crate::bitsliced::{BitslicedInput, BitslicedState};
+
+/// Linear relation between the key bits and the sum of a keystream bit
+/// over all the values of a cube of IV bits.
+pub struct SuperpolyRelation {
+    /// IV bits whose values are summed over.
+    pub cube: Vec<usize>,
+    
+    /// Keystream bit position (the first keystream bit being 0).
+    pub output_bit: usize,
+    
+    /// Key bits involved in the superpoly.
+    pub key_mask: u64,
+    
+    /// Constant term of the superpoly.
+    pub constant: bool
+}
+
+impl SuperpolyRelation {
+    /// Evaluate the superpoly over a key, which gives the expected
+    /// value of the cube sum.
+    pub fn evaluate(&self, key: u64) -> bool {
+        ((key & self.key_mask).count_ones() & 1 != 0) ^ self.constant
+    }
+}
+
+impl fmt::Display for SuperpolyRelation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut terms: Vec<String> = (0..64)
+            .filter(|bit_pos| (self.key_mask >> bit_pos) & 1 != 0)
+            .map(|bit_pos| format!("k{}", bit_pos))
+            .collect();
+        if self.constant {
+            terms.push("1".to_string());
+        }
+        write!(formatter, "cube {:?}, keystream bit {}: {}", self.cube, self.output_bit, terms.join(" ^ "))
+    }
+}
+
+/// Parameters of a cube attack against GEA-1 or GEA-2.
+pub struct CubeAttack {
+    pub algorithm: LfsrAlgorithm,
+    pub direction: LinkDirection,
+    
+    /// Number of mixing clocks of the S (or W) register initialization.
+    pub mixing_clocks: usize,
+    
+    /// Value of the IV bits which are not part of the cube.
+    pub base_iv: u32,
+    
+    /// Number of keystream bits (up to 64) whose superpolys are examined.
+    pub num_output_bits: usize,
+    
+    /// Number of BLR linearity tests run before accepting a superpoly
+    /// as linear.
+    pub num_linearity_tests: usize,
+    
+    /// Seed of the generator of the random keys used for the linearity
+    /// tests.
+    pub seed: u64
+}
+
+impl CubeAttack {
+    pub fn new(algorithm: LfsrAlgorithm, mixing_clocks: usize) -> Self {
+        Self {
+            algorithm,
+            direction: LinkDirection::Uplink,
+            mixing_clocks,
+            base_iv: 0,
+            num_output_bits: 64,
+            num_linearity_tests: 32,
+            seed: 0x9e3779b97f4a7c15
+        }
+    }
+    
+    /// Sum the first keystream bits over all the values of the IV bits
+    /// of the cube, for a given key. The n-th bit of the result is the
+    /// sum for the n-th keystream bit.
+    ///
+    /// Values of the cube are spread over the lanes of the bitsliced
+    /// implementation, so that 64 of them are processed at once.
+    ///
+    /// Panics if more than 64 keystream bits are examined.
+    pub fn cube_sum(&self, key: u64, cube: &[usize]) -> u64 {
+        assert!(self.num_output_bits <= 64, "at most 64 keystream bits can be summed");
+        let num_values: u64 = 1 << cube.len();
+        let mut sums = 0;
+        
+        let mut first_value = 0;
+        while first_value < num_values {
+            let mut input = BitslicedInput::broadcast(key, self.base_iv, self.direction);
+            let mut lanes = 0;
+            for lane in 0..64.min(num_values - first_value) {
+                let value = first_value + lane;
+                lanes |= 1 << lane;
+                for (cube_pos, &iv_bit) in cube.iter().enumerate() {
+                    let mask = 1 << lane;
+                    if (value >> cube_pos) & 1 != 0 {
+                        input.iv[iv_bit] |= mask;
+                    }
+                    else {
+                        input.iv[iv_bit] &= !mask;
+                    }
+                }
+            }
+            
+            let mut state = BitslicedState::initialize(self.algorithm, &input, self.mixing_clocks);
+            for (output_bit, word) in state.generate_words(self.num_output_bits).iter().enumerate() {
+                sums ^= (((word & lanes).count_ones() & 1) as u64) << output_bit;
+            }
+            first_value += 64;
+        }
+        sums
+    }
+    
+    /// Detect the keystream bits whose superpoly over the given cube is
+    /// linear (and not constant) in the key bits, through BLR linearity
+    /// tests, and recover these superpolys.
+    pub fn find_superpolys(&self, cube: &[usize]) -> Vec<SuperpolyRelation> {
+        self.find_superpolys_with(cube, |key| self.cube_sum(key, cube))
+    }
+    
+    /// Run the linearity tests and the superpoly recovery of
+    /// `find_superpolys` over any function returning the cube sums for a
+    /// given key.
+    pub(crate) fn find_superpolys_with<F: FnMut(u64) -> u64>(&self, cube: &[usize], mut cube_sum: F)
+            -> Vec<SuperpolyRelation> {
+        assert!(self.num_output_bits <= 64, "at most 64 keystream bits can be summed");
+        let zero_sum = cube_sum(0);
+        
+        let mut random_state = self.seed;
+        let mut linear_bits = if self.num_output_bits == 64 { !0 } else { (1 << self.num_output_bits) - 1 };
+        for _num_test in 0..self.num_linearity_tests {
+            let first_key = next_random(&mut random_state);
+            let second_key = next_random(&mut random_state);
+            linear_bits &= !(cube_sum(first_key) ^ cube_sum(second_key) ^
+                zero_sum ^ cube_sum(first_key ^ second_key));
+            if linear_bits == 0 {
+                return vec![];
+            }
+        }
+        
+        let key_bit_sums: Vec<u64> = (0..64).map(|bit_pos| cube_sum(1 << bit_pos) ^ zero_sum).collect();
+        (0..self.num_output_bits)
+            .filter(|output_bit| (linear_bits >> output_bit) & 1 != 0)
+            .map(|output_bit| SuperpolyRelation {
+                cube: cube.to_vec(),
+                output_bit,
+                key_mask: key_bit_sums.iter().enumerate()
+                    .fold(0, |mask, (bit_pos, sums)| mask | (((sums >> output_bit) & 1) << bit_pos)),
+                constant: (zero_sum >> output_bit) & 1 != 0
+            })
+            .filter(|relation| relation.key_mask != 0)
+            .collect()
+    }
+    
+    /// Search linear superpolys over a list of cubes.
+    pub fn search(&self, cubes: &[Vec<usize>]) -> Vec<SuperpolyRelation> {
+        cubes.iter().flat_map(|cube| self.find_superpolys(cube)).collect()
+    }
+}
+
+/// Sum the first keystream bits (up to 64) over all the values of the
+/// IV bits of the cube, querying an oracle which returns the keystream
+/// (or the encryption of zeroes) for a given IV, as done in the online
+/// phase of the attack.
+///
+/// Panics if more than 64 keystream bits are requested.
+pub fn cube_sum_with_oracle<F: FnMut(u32) -> Vec<u8>>(cube: &[usize], base_iv: u32, num_output_bits: usize,
+        mut oracle: F) -> u64 {
+    assert!(num_output_bits <= 64, "at most 64 keystream bits can be summed");
+    let mut sums = 0;
+    for value in 0..(1u64 << cube.len()) {
+        let mut iv = base_iv;
+        for (cube_pos, &iv_bit) in cube.iter().enumerate() {
+            iv = (iv & !(1 << iv_bit)) | ((((value >> cube_pos) & 1) as u32) << iv_bit);
+        }
+        let keystream = oracle(iv);
+        for output_bit in 0..num_output_bits {
+            sums ^= (((keystream[output_bit / 8] >> (output_bit % 8)) & 1) as u64) << output_bit;
+        }
+    }
+    sums
+}
+
+/// xorshift64* pseudo-random generator, used for drawing keys for the
+/// linearity tests.
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state >> 12;
+    *state ^= *state << 25;
+    *state ^= *state >> 27;
+    state.wrapping_mul(0x2545f4914f6cdd1d)
+}
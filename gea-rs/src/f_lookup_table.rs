@@ -32,7 +32,6 @@
 /// Please see the "generate_f_lookup_table.py" script present in the
 /// current directory for context about how the following lookup table
 /// has been originally generated.
-
 pub const F_LOOKUP_TABLE: [bool; 128] = [
     false,
     false,
@@ -0,0 +1,291 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : gb.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Gb interface: NS (3GPP TS 48.016) and BSSGP (3GPP TS 48.018)
+//  encoding and decoding, for extracting and re-injecting the LLC PDUs
+//  carried by UL-UNITDATA and DL-UNITDATA PDUs
+//-----------------------------------------------------------------------------/
+
+
+use std::fmt;
+
+use crate::link_direction::LinkDirection;
+use crate::llc::frame::{LlcError, LlcFrame};
+use crate::llc::session::LlcSession;
+
+/// NS PDU type of NS-UNITDATA.
+pub const NS_UNITDATA: u8 = 0x00;
+
+/// BSSGP PDU types of DL-UNITDATA and UL-UNITDATA.
+pub const BSSGP_DL_UNITDATA: u8 = 0x00;
+pub const BSSGP_UL_UNITDATA: u8 = 0x01;
+
+/// BSSGP information element identifiers.
+pub const BSSGP_IEI_CELL_IDENTIFIER: u8 = 0x08;
+pub const BSSGP_IEI_LLC_PDU: u8 = 0x0e;
+pub const BSSGP_IEI_PDU_LIFETIME: u8 = 0x16;
+
+/// Error encountered when decoding Gb PDUs.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GbError {
+    /// A PDU or information element is truncated.
+    TooShort,
+    
+    /// A UNITDATA PDU carries no LLC-PDU information element.
+    MissingLlcPdu,
+    
+    /// The LLC PDU could not be parsed or deciphered.
+    Llc(LlcError)
+}
+
+impl fmt::Display for GbError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GbError::TooShort => formatter.write_str("Gb PDU too short"),
+            GbError::MissingLlcPdu => formatter.write_str("BSSGP UNITDATA without LLC PDU"),
+            GbError::Llc(error) => write!(formatter, "{}", error)
+        }
+    }
+}
+
+impl std::error::Error for GbError {}
+
+impl From<LlcError> for GbError {
+    fn from(error: LlcError) -> Self {
+        GbError::Llc(error)
+    }
+}
+
+/// NS PDU.
+#[derive(Clone, PartialEq, Debug)]
+pub enum NsPdu {
+    /// NS-UNITDATA, carrying a BSSGP PDU (NS SDU) for a BVC.
+    Unitdata {
+        /// NS SDU control bits.
+        sdu_control: u8,
+        
+        /// BSSGP Virtual Connection Identifier.
+        bvci: u16,
+        
+        sdu: Vec<u8>
+    },
+    
+    /// Other NS PDUs (NS-RESET, NS-ALIVE...), left undecoded.
+    Other {
+        pdu_type: u8,
+        data: Vec<u8>
+    }
+}
+
+impl NsPdu {
+    pub fn parse(data: &[u8]) -> Result<Self, GbError> {
+        let pdu_type = *data.first().ok_or(GbError::TooShort)?;
+        if pdu_type != NS_UNITDATA {
+            return Ok(NsPdu::Other { pdu_type, data: data[1..].to_vec() });
+        }
+        if data.len() < 4 {
+            return Err(GbError::TooShort);
+        }
+        Ok(NsPdu::Unitdata {
+            sdu_control: data[1],
+            bvci: u16::from_be_bytes([data[2], data[3]]),
+            sdu: data[4..].to_vec()
+        })
+    }
+    
+    pub fn serialize(&self) -> Vec<u8> {
+        match self {
+            NsPdu::Unitdata { sdu_control, bvci, sdu } => {
+                let mut data = vec![NS_UNITDATA, *sdu_control];
+                data.extend_from_slice(&bvci.to_be_bytes());
+                data.extend_from_slice(sdu);
+                data
+            },
+            NsPdu::Other { pdu_type, data } => {
+                let mut serialized = vec![*pdu_type];
+                serialized.extend_from_slice(data);
+                serialized
+            }
+        }
+    }
+}
+
+/// BSSGP information element, in TLV format.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BssgpElement {
+    pub iei: u8,
+    pub value: Vec<u8>
+}
+
+impl BssgpElement {
+    pub fn new(iei: u8, value: &[u8]) -> Self {
+        Self { iei, value: value.to_vec() }
+    }
+    
+    /// Parse a sequence of information elements. Their length indicator
+    /// is one octet long with its extension bit set (lengths up to 127),
+    /// or two octets long otherwise.
+    pub fn parse_list(data: &[u8]) -> Result<Vec<Self>, GbError> {
+        let mut elements = vec![];
+        let mut position = 0;
+        while position < data.len() {
+            let iei = data[position];
+            let length_octet = *data.get(position + 1).ok_or(GbError::TooShort)?;
+            let (length, length_size) = if length_octet & 0x80 != 0 {
+                ((length_octet & 0x7f) as usize, 1)
+            } else {
+                let low_octet = *data.get(position + 2).ok_or(GbError::TooShort)?;
+                ((((length_octet & 0x7f) as usize) << 8) | low_octet as usize, 2)
+            };
+            position += 1 + length_size;
+            let value = data.get(position..position + length).ok_or(GbError::TooShort)?;
+            elements.push(Self::new(iei, value));
+            position += length;
+        }
+        Ok(elements)
+    }
+    
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = vec![self.iei];
+        if self.value.len() < 0x80 {
+            data.push(0x80 | self.value.len() as u8);
+        } else {
+            data.extend_from_slice(&((self.value.len() & 0x7fff) as u16).to_be_bytes());
+        }
+        data.extend_from_slice(&self.value);
+        data
+    }
+}
+
+/// BSSGP UL-UNITDATA (uplink) or DL-UNITDATA (downlink) PDU.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BssgpUnitdata {
+    pub direction: LinkDirection,
+    pub tlli: u32,
+    pub qos_profile: [u8; 3],
+    
+    /// Information elements, the LLC-PDU being the last one.
+    pub elements: Vec<BssgpElement>
+}
+
+impl BssgpUnitdata {
+    /// Value of the LLC-PDU information element.
+    pub fn llc_pdu(&self) -> Option<&[u8]> {
+        self.elements.iter()
+            .find(|element| element.iei == BSSGP_IEI_LLC_PDU)
+            .map(|element| &element.value[..])
+    }
+    
+    /// Replace the LLC PDU carried, e.g. after ciphering or deciphering it.
+    pub fn set_llc_pdu(&mut self, llc_pdu: &[u8]) {
+        self.elements.retain(|element| element.iei != BSSGP_IEI_LLC_PDU);
+        self.elements.push(BssgpElement::new(BSSGP_IEI_LLC_PDU, llc_pdu));
+    }
+    
+    /// Decipher the LLC PDU carried within an LLC session, using the
+    /// TLLI and direction of the PDU.
+    pub fn decrypt_llc_pdu(&self, session: &mut LlcSession) -> Result<LlcFrame, GbError> {
+        let llc_pdu = self.llc_pdu().ok_or(GbError::MissingLlcPdu)?;
+        Ok(session.decrypt_frame(self.tlli, self.direction, llc_pdu)?)
+    }
+}
+
+/// BSSGP PDU.
+#[derive(Clone, PartialEq, Debug)]
+pub enum BssgpPdu {
+    Unitdata(BssgpUnitdata),
+    
+    /// Other BSSGP PDUs (signalling, flow control...), left undecoded.
+    Other {
+        pdu_type: u8,
+        data: Vec<u8>
+    }
+}
+
+impl BssgpPdu {
+    /// Parse a BSSGP PDU, i.e. the SDU of an NS-UNITDATA PDU. The TLLI
+    /// and QoS profile of UNITDATA PDUs have fixed positions, and are
+    /// followed by information elements.
+    pub fn parse(data: &[u8]) -> Result<Self, GbError> {
+        let pdu_type = *data.first().ok_or(GbError::TooShort)?;
+        let direction = match pdu_type {
+            BSSGP_DL_UNITDATA => LinkDirection::Downlink,
+            BSSGP_UL_UNITDATA => LinkDirection::Uplink,
+            _ => return Ok(BssgpPdu::Other { pdu_type, data: data[1..].to_vec() })
+        };
+        if data.len() < 8 {
+            return Err(GbError::TooShort);
+        }
+        Ok(BssgpPdu::Unitdata(BssgpUnitdata {
+            direction,
+            tlli: u32::from_be_bytes([data[1], data[2], data[3], data[4]]),
+            qos_profile: [data[5], data[6], data[7]],
+            elements: BssgpElement::parse_list(&data[8..])?
+        }))
+    }
+    
+    pub fn serialize(&self) -> Vec<u8> {
+        match self {
+            BssgpPdu::Unitdata(unitdata) => {
+                let mut data = vec![match unitdata.direction {
+                    LinkDirection::Downlink => BSSGP_DL_UNITDATA,
+                    LinkDirection::Uplink => BSSGP_UL_UNITDATA
+                }];
+                data.extend_from_slice(&unitdata.tlli.to_be_bytes());
+                data.extend_from_slice(&unitdata.qos_profile);
+                for element in &unitdata.elements {
+                    data.extend(element.serialize());
+                }
+                data
+            },
+            BssgpPdu::Other { pdu_type, data } => {
+                let mut serialized = vec![*pdu_type];
+                serialized.extend_from_slice(data);
+                serialized
+            }
+        }
+    }
+}
+
+/// Parse the BSSGP UNITDATA PDU carried by an NS PDU, if any, along
+/// with the BVCI of its NS-UNITDATA PDU.
+pub fn parse_unitdata(ns_data: &[u8]) -> Result<Option<(u16, BssgpUnitdata)>, GbError> {
+    match NsPdu::parse(ns_data)? {
+        NsPdu::Unitdata { bvci, sdu, .. } => match BssgpPdu::parse(&sdu)? {
+            BssgpPdu::Unitdata(unitdata) => Ok(Some((bvci, unitdata))),
+            BssgpPdu::Other { .. } => Ok(None)
+        },
+        NsPdu::Other { .. } => Ok(None)
+    }
+}
+
+/// Build an NS-UNITDATA PDU carrying a BSSGP UNITDATA PDU.
+pub fn serialize_unitdata(bvci: u16, unitdata: &BssgpUnitdata) -> Vec<u8> {
+    NsPdu::Unitdata {
+        sdu_control: 0,
+        bvci,
+        sdu: BssgpPdu::Unitdata(unitdata.clone()).serialize()
+    }.serialize()
+}
@@ -54,7 +54,7 @@ impl GEA1State {
                 ((s_register.0 & ((1 << 16) - 1)) << (64 - 16)), 64),
             c_register: CRegister::initialize((s_register.0 >> 32) |
                 ((s_register.0 & ((1 << 32) - 1)) << 32), 64),
-            s_register: s_register
+            s_register
         }
     }
     
@@ -79,17 +79,24 @@ impl GEA1State {
         for _num_byte in 0..num_bytes {
             let mut new_byte = 0;
             for num_bit in 0..8 {
-                new_byte |= (self.a_register.f_function() as u8 ^
-                    self.b_register.f_function() as u8 ^
-                    self.c_register.f_function() as u8) << num_bit;
-                    
-                self.a_register.clock(None);
-                self.b_register.clock(None);
-                self.c_register.clock(None);
+                new_byte |= self.next_bit() << num_bit;
             }
             keystream.push(new_byte);
         }
         
         keystream
     }
+    
+    /// Generate the next keystream bit, for keystream requested in
+    /// a number of bits which is not a multiple of 8.
+    pub fn next_bit(&mut self) -> u8 {
+        let bit = self.a_register.f_function() as u8 ^
+            self.b_register.f_function() as u8 ^
+            self.c_register.f_function() as u8;
+            
+        self.a_register.clock(None);
+        self.b_register.clock(None);
+        self.c_register.clock(None);
+        bit
+    }
 }
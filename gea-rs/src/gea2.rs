@@ -58,7 +58,7 @@ impl GEA2State {
             c_register: CRegister::initialize((w_register.0 >> 51) |
                 ((w_register.0 & ((1 << 51) - 1)) << (97 - 51)), 97),
             d_register: DRegister::initialize(w_register.0, 97),
-            w_register: w_register
+            w_register
         }
     }
     
@@ -83,19 +83,26 @@ impl GEA2State {
         for _num_byte in 0..num_bytes {
             let mut new_byte = 0;
             for num_bit in 0..8 {
-                new_byte |= (self.a_register.f_function() as u8 ^
-                    self.b_register.f_function() as u8 ^
-                    self.c_register.f_function() as u8 ^
-                    self.d_register.f_function() as u8) << num_bit;
-                    
-                self.a_register.clock(None);
-                self.b_register.clock(None);
-                self.c_register.clock(None);
-                self.d_register.clock(None);
+                new_byte |= self.next_bit() << num_bit;
             }
             keystream.push(new_byte);
         }
         
         keystream
     }
+    
+    /// Generate the next keystream bit, for keystream requested in
+    /// a number of bits which is not a multiple of 8.
+    pub fn next_bit(&mut self) -> u8 {
+        let bit = self.a_register.f_function() as u8 ^
+            self.b_register.f_function() as u8 ^
+            self.c_register.f_function() as u8 ^
+            self.d_register.f_function() as u8;
+            
+        self.a_register.clock(None);
+        self.b_register.clock(None);
+        self.c_register.clock(None);
+        self.d_register.clock(None);
+        bit
+    }
 }
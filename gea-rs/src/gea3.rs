@@ -0,0 +1,77 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : gea3.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Implementation of the GPRS encryption algorithm GEA3, based on
+//  KASUMI, as specified by 3GPP TS 55.216
+//-----------------------------------------------------------------------------/
+
+
+use crate::kasumi::KgCore;
+use crate::link_direction::LinkDirection;
+
+/// Value of the KGCORE CA parameter for GEA3 and GEA4.
+pub const GEA_CA: u8 = 0xff;
+
+pub struct GEA3State {
+    kg_core: KgCore,
+    
+    /// Keystream bytes generated from the last block and not used yet.
+    pending_bytes: Vec<u8>
+}
+
+impl GEA3State {
+    /// Create a new GEA3 encryption/decryption object from a 64-bit
+    /// key, IV and direction bit. The KASUMI key is made of the key
+    /// repeated twice.
+    pub fn initialize(key: u64, iv: u32, direction: LinkDirection) -> Self {
+        Self::initialize_kgcore(((key as u128) << 64) | key as u128, iv, direction)
+    }
+    
+    /// Create an object from the 128-bit KGCORE key.
+    pub(crate) fn initialize_kgcore(key: u128, iv: u32, direction: LinkDirection) -> Self {
+        Self {
+            kg_core: KgCore::new(GEA_CA, 0, iv, direction == LinkDirection::Downlink, 0, key),
+            pending_bytes: vec![]
+        }
+    }
+    
+    /// Encrypt or decrypt a stream of data in a cipher
+    /// stream fashion, xor'ing a byte of ciphertext or
+    /// plaintext at once with keystream bytes.
+    pub fn crypt_stream(&mut self, stream: &[u8]) -> Vec<u8> {
+        let keystream = self.generate_stream(stream.len());
+        stream.iter().zip(keystream).map(|(byte, key_byte)| byte ^ key_byte).collect()
+    }
+    
+    /// Generate an arbitrary quantity of keystream, following the
+    /// keystream already generated.
+    pub fn generate_stream(&mut self, num_bytes: usize) -> Vec<u8> {
+        while self.pending_bytes.len() < num_bytes {
+            let block = self.kg_core.next_block();
+            self.pending_bytes.extend_from_slice(&block.to_be_bytes());
+        }
+        self.pending_bytes.drain(..num_bytes).collect()
+    }
+}
@@ -0,0 +1,57 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : gea4.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Implementation of the GPRS encryption algorithm GEA4, based on
+//  KASUMI with a 128-bit key, as specified by 3GPP TS 55.226
+//-----------------------------------------------------------------------------/
+
+
+use crate::gea3::GEA3State;
+use crate::link_direction::LinkDirection;
+
+/// GEA4 is the KGCORE construction of GEA3, keyed with the 128-bit
+/// Kc128 instead of Kc repeated twice.
+pub struct GEA4State(GEA3State);
+
+impl GEA4State {
+    /// Create a new GEA4 encryption/decryption object from a 128-bit
+    /// key, IV and direction bit.
+    pub fn initialize(key: u128, iv: u32, direction: LinkDirection) -> Self {
+        Self(GEA3State::initialize_kgcore(key, iv, direction))
+    }
+    
+    /// Encrypt or decrypt a stream of data in a cipher
+    /// stream fashion, xor'ing a byte of ciphertext or
+    /// plaintext at once with keystream bytes.
+    pub fn crypt_stream(&mut self, stream: &[u8]) -> Vec<u8> {
+        self.0.crypt_stream(stream)
+    }
+    
+    /// Generate an arbitrary quantity of keystream, following the
+    /// keystream already generated.
+    pub fn generate_stream(&mut self, num_bytes: usize) -> Vec<u8> {
+        self.0.generate_stream(num_bytes)
+    }
+}
@@ -0,0 +1,144 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : gea_algorithm.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Selection of the GPRS encryption algorithm, with a common API for
+//  generating keystream
+//-----------------------------------------------------------------------------/
+
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::gea1::GEA1State;
+use crate::gea2::GEA2State;
+use crate::gea3::GEA3State;
+use crate::gea4::GEA4State;
+use crate::registers::s_register::SRegister;
+use crate::registers::w_register::WRegister;
+use crate::link_direction::LinkDirection;
+
+/// GPRS encryption algorithm to be used.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GEAAlgorithm {
+    GEA1,
+    GEA2,
+    GEA3,
+    GEA4
+}
+
+impl GEAAlgorithm {
+    /// Size of the key, in bits: 64 for Kc, 128 for Kc128.
+    pub fn key_size(&self) -> u32 {
+        match self {
+            GEAAlgorithm::GEA1 | GEAAlgorithm::GEA2 | GEAAlgorithm::GEA3 => 64,
+            GEAAlgorithm::GEA4 => 128
+        }
+    }
+    
+    /// Check that a key fits in the key size of the algorithm.
+    pub fn check_key(&self, key: u128) -> Result<(), InvalidKeySize> {
+        match self.key_size() {
+            128 => Ok(()),
+            key_size if key >> key_size == 0 => Ok(()),
+            _ => Err(InvalidKeySize(*self))
+        }
+    }
+    
+    /// Generate an arbitrary quantity of keystream from a key, IV and
+    /// direction bit. Keys wider than the key size of the algorithm are
+    /// rejected.
+    pub fn generate_stream(&self, key: u128, iv: u32, direction: LinkDirection, num_bytes: usize)
+            -> Result<Vec<u8>, InvalidKeySize> {
+        self.check_key(key)?;
+        Ok(match self {
+            GEAAlgorithm::GEA1 => GEA1State::initialize(SRegister::initialize(key as u64, iv, direction))
+                .generate_stream(num_bytes),
+            GEAAlgorithm::GEA2 => GEA2State::initialize(WRegister::initialize(key as u64, iv, direction))
+                .generate_stream(num_bytes),
+            GEAAlgorithm::GEA3 => GEA3State::initialize(key as u64, iv, direction).generate_stream(num_bytes),
+            GEAAlgorithm::GEA4 => GEA4State::initialize(key, iv, direction).generate_stream(num_bytes)
+        })
+    }
+    
+    /// Encrypt or decrypt a stream of data from a key, IV and
+    /// direction bit. Keys wider than the key size of the algorithm are
+    /// rejected.
+    pub fn crypt_stream(&self, key: u128, iv: u32, direction: LinkDirection, stream: &[u8])
+            -> Result<Vec<u8>, InvalidKeySize> {
+        self.check_key(key)?;
+        Ok(match self {
+            GEAAlgorithm::GEA1 => GEA1State::initialize(SRegister::initialize(key as u64, iv, direction))
+                .crypt_stream(stream),
+            GEAAlgorithm::GEA2 => GEA2State::initialize(WRegister::initialize(key as u64, iv, direction))
+                .crypt_stream(stream),
+            GEAAlgorithm::GEA3 => GEA3State::initialize(key as u64, iv, direction).crypt_stream(stream),
+            GEAAlgorithm::GEA4 => GEA4State::initialize(key, iv, direction).crypt_stream(stream)
+        })
+    }
+}
+
+/// Error returned when a key is wider than the key size of the algorithm
+/// it is used with.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct InvalidKeySize(pub GEAAlgorithm);
+
+impl fmt::Display for InvalidKeySize {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the key of {:?} is {} bits long", self.0, self.0.key_size())
+    }
+}
+
+impl std::error::Error for InvalidKeySize {}
+
+/// LFSR-based GPRS encryption algorithm, as handled by the analysis
+/// modules (which model the S or W register, then the A, B, C and D
+/// registers).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LfsrAlgorithm {
+    GEA1,
+    GEA2
+}
+
+impl From<LfsrAlgorithm> for GEAAlgorithm {
+    fn from(algorithm: LfsrAlgorithm) -> Self {
+        match algorithm {
+            LfsrAlgorithm::GEA1 => GEAAlgorithm::GEA1,
+            LfsrAlgorithm::GEA2 => GEAAlgorithm::GEA2
+        }
+    }
+}
+
+/// Fails for the algorithms which are not LFSR-based, returning them.
+impl TryFrom<GEAAlgorithm> for LfsrAlgorithm {
+    type Error = GEAAlgorithm;
+    
+    fn try_from(algorithm: GEAAlgorithm) -> Result<Self, GEAAlgorithm> {
+        match algorithm {
+            GEAAlgorithm::GEA1 => Ok(LfsrAlgorithm::GEA1),
+            GEAAlgorithm::GEA2 => Ok(LfsrAlgorithm::GEA2),
+            _ => Err(algorithm)
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : gmm.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Decoding of the GPRS mobility management (GMM) messages negotiating
+//  the ciphering of a subscriber, as specified by 3GPP TS 24.008
+//-----------------------------------------------------------------------------/
+
+
+use std::convert::TryInto;
+use std::fmt;
+
+use crate::gea_algorithm::GEAAlgorithm;
+
+/// SAPI of the LLC link carrying GMM messages.
+pub const GMM_SAPI: u8 = 1;
+
+/// Protocol discriminator of GMM messages.
+pub const GMM_PROTOCOL_DISCRIMINATOR: u8 = 0x08;
+
+// GMM message types:
+pub const ATTACH_REQUEST: u8 = 0x01;
+pub const ROUTING_AREA_UPDATE_REQUEST: u8 = 0x08;
+pub const AUTHENTICATION_AND_CIPHERING_REQUEST: u8 = 0x12;
+pub const AUTHENTICATION_AND_CIPHERING_RESPONSE: u8 = 0x13;
+pub const AUTHENTICATION_AND_CIPHERING_REJECT: u8 = 0x14;
+pub const AUTHENTICATION_AND_CIPHERING_FAILURE: u8 = 0x1c;
+
+// Information element identifiers:
+pub const IEI_RAND: u8 = 0x21;
+pub const IEI_SRES: u8 = 0x22;
+pub const IEI_MS_NETWORK_CAPABILITY: u8 = 0x31;
+
+/// Identifier of the GPRS CKSN element (half-octet IEI, in the high
+/// nibble of the octet).
+pub const IEI_CKSN: u8 = 0x80;
+
+/// Value of the CKSN meaning that no key is available.
+pub const NO_KEY_AVAILABLE: u8 = 7;
+
+/// Error encountered when parsing GMM messages.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GmmError {
+    /// The message is shorter than its mandatory elements, or an element
+    /// overflows it.
+    TooShort,
+    
+    /// The message belongs to another protocol (e.g. session management).
+    NotGmm
+}
+
+impl fmt::Display for GmmError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            GmmError::TooShort => "GMM message too short",
+            GmmError::NotGmm => "not a GMM message"
+        })
+    }
+}
+
+impl std::error::Error for GmmError {}
+
+/// GEA algorithm of a "type of ciphering algorithm" value, none for
+/// "ciphering not used" (0) and for GEA/5 to GEA/7, which are not
+/// implemented.
+pub fn ciphering_algorithm(value: u8) -> Option<GEAAlgorithm> {
+    match value & 0x07 {
+        1 => Some(GEAAlgorithm::GEA1),
+        2 => Some(GEAAlgorithm::GEA2),
+        3 => Some(GEAAlgorithm::GEA3),
+        4 => Some(GEAAlgorithm::GEA4),
+        _ => None
+    }
+}
+
+/// MS network capability element value, telling among others the GEA
+/// algorithms supported by the MS.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MsNetworkCapability(pub Vec<u8>);
+
+impl MsNetworkCapability {
+    /// Supported algorithms: GEA/1 is indicated in the first octet, the
+    /// following ones in the second octet (when present). GEA/5 and
+    /// later are not implemented, hence not reported.
+    pub fn supported_algorithms(&self) -> Vec<GEAAlgorithm> {
+        let mut algorithms = vec![];
+        if self.0.first().is_some_and(|octet| octet & 0x80 != 0) {
+            algorithms.push(GEAAlgorithm::GEA1);
+        }
+        if let Some(octet) = self.0.get(1) {
+            for (bit, algorithm) in [GEAAlgorithm::GEA2, GEAAlgorithm::GEA3, GEAAlgorithm::GEA4]
+                    .iter().enumerate() {
+                if octet & (0x40 >> bit) != 0 {
+                    algorithms.push(*algorithm);
+                }
+            }
+        }
+        algorithms
+    }
+}
+
+/// GMM message, with the elements relevant to ciphering decoded.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GmmMessage {
+    AttachRequest {
+        ms_network_capability: MsNetworkCapability,
+        
+        /// CKSN of the Kc the MS holds (3 bits).
+        cksn: u8
+    },
+    
+    RoutingAreaUpdateRequest {
+        /// CKSN of the Kc the MS holds (3 bits).
+        cksn: u8,
+        
+        ms_network_capability: Option<MsNetworkCapability>
+    },
+    
+    AuthenticationAndCipheringRequest {
+        /// Type of ciphering algorithm (3 bits, see "ciphering_algorithm").
+        ciphering_algorithm: u8,
+        
+        /// A&C reference number (4 bits), repeated in the response.
+        reference_number: u8,
+        
+        rand: Option<[u8; 16]>,
+        
+        /// CKSN of the Kc to be derived from RAND (3 bits).
+        cksn: Option<u8>
+    },
+    
+    AuthenticationAndCipheringResponse {
+        /// A&C reference number (4 bits).
+        reference_number: u8,
+        
+        sres: Option<[u8; 4]>
+    },
+    
+    AuthenticationAndCipheringReject,
+    
+    AuthenticationAndCipheringFailure {
+        /// GMM cause.
+        cause: u8
+    },
+    
+    /// Message of another type, left undecoded.
+    Other {
+        message_type: u8
+    }
+}
+
+impl GmmMessage {
+    /// Parse a GMM message from the information field of an LLC frame.
+    pub fn parse(data: &[u8]) -> Result<Self, GmmError> {
+        let header = data.get(..2).ok_or(GmmError::TooShort)?;
+        if header[0] & 0x0f != GMM_PROTOCOL_DISCRIMINATOR {
+            return Err(GmmError::NotGmm);
+        }
+        let body = &data[2..];
+        let octet = |position: usize| body.get(position).copied().ok_or(GmmError::TooShort);
+        
+        Ok(match header[1] {
+            ATTACH_REQUEST => {
+                let (ms_network_capability, position) = length_value(body, 0)?;
+                GmmMessage::AttachRequest {
+                    ms_network_capability: MsNetworkCapability(ms_network_capability.to_vec()),
+                    cksn: (octet(position)? >> 4) & 0x07
+                }
+            },
+            ROUTING_AREA_UPDATE_REQUEST => {
+                // Update type and CKSN, then old RAI (6 octets) and MS
+                // radio access capability
+                let cksn = (octet(0)? >> 4) & 0x07;
+                let (_, position) = length_value(body, 7)?;
+                let elements = optional_elements(&body[position..], &[(0x19, 4), (0x17, 2), (0x27, 3)])?;
+                GmmMessage::RoutingAreaUpdateRequest {
+                    cksn,
+                    ms_network_capability: find_element(&elements, IEI_MS_NETWORK_CAPABILITY)
+                        .map(|value| MsNetworkCapability(value.to_vec()))
+                }
+            },
+            AUTHENTICATION_AND_CIPHERING_REQUEST => {
+                let (ciphering, reference) = (octet(0)?, octet(1)?);
+                let elements = optional_elements(&body[2..], &[(IEI_RAND, 17)])?;
+                GmmMessage::AuthenticationAndCipheringRequest {
+                    ciphering_algorithm: ciphering & 0x07,
+                    reference_number: reference >> 4,
+                    rand: find_element(&elements, IEI_RAND).map(|value| value.try_into().unwrap()),
+                    cksn: find_element(&elements, IEI_CKSN).map(|value| value[0] & 0x07)
+                }
+            },
+            AUTHENTICATION_AND_CIPHERING_RESPONSE => {
+                let reference = octet(0)?;
+                let elements = optional_elements(&body[1..], &[(IEI_SRES, 5)])?;
+                GmmMessage::AuthenticationAndCipheringResponse {
+                    reference_number: reference & 0x0f,
+                    sres: find_element(&elements, IEI_SRES).map(|value| value.try_into().unwrap())
+                }
+            },
+            AUTHENTICATION_AND_CIPHERING_REJECT => GmmMessage::AuthenticationAndCipheringReject,
+            AUTHENTICATION_AND_CIPHERING_FAILURE => GmmMessage::AuthenticationAndCipheringFailure { cause: octet(0)? },
+            message_type => GmmMessage::Other { message_type }
+        })
+    }
+}
+
+/// Parse a length-value element at a position, returning its value and
+/// the position following it.
+fn length_value(data: &[u8], position: usize) -> Result<(&[u8], usize), GmmError> {
+    let length = *data.get(position).ok_or(GmmError::TooShort)? as usize;
+    let value = data.get(position + 1..position + 1 + length).ok_or(GmmError::TooShort)?;
+    Ok((value, position + 1 + length))
+}
+
+/// Parse the optional elements of a message into their identifiers and
+/// values. Elements with a half-octet identifier are single octets (their
+/// value being the low nibble), those listed in "fixed_lengths" are TV
+/// elements of the given total length, and the other ones are TLV
+/// elements.
+fn optional_elements<'a>(data: &'a [u8], fixed_lengths: &[(u8, usize)]) -> Result<Vec<(u8, &'a [u8])>, GmmError> {
+    let mut elements = vec![];
+    let mut position = 0;
+    while position < data.len() {
+        let identifier = data[position];
+        if identifier & 0x80 != 0 {
+            elements.push((identifier & 0xf0, &data[position..position + 1]));
+            position += 1;
+        } else if let Some((_, length)) = fixed_lengths.iter().find(|(fixed_identifier, _)| *fixed_identifier == identifier) {
+            elements.push((identifier, data.get(position + 1..position + length).ok_or(GmmError::TooShort)?));
+            position += length;
+        } else {
+            let (value, next_position) = length_value(data, position + 1)?;
+            elements.push((identifier, value));
+            position = next_position;
+        }
+    }
+    Ok(elements)
+}
+
+fn find_element<'a>(elements: &[(u8, &'a [u8])], identifier: u8) -> Option<&'a [u8]> {
+    elements.iter().find(|(element_identifier, _)| *element_identifier == identifier).map(|(_, value)| *value)
+}
+
+/// Ciphering information about a subscriber, gathered from its GMM
+/// messages.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct GmmSubscriber {
+    /// Algorithms supported by the MS, from its MS network capability.
+    pub supported_algorithms: Vec<GEAAlgorithm>,
+    
+    /// Algorithm selected by the SGSN, none when ciphering is not used.
+    pub algorithm: Option<GEAAlgorithm>,
+    
+    /// RAND of the latest authentication, from which Kc is derived.
+    pub rand: Option<[u8; 16]>,
+    
+    /// CKSN of the Kc in use.
+    pub cksn: Option<u8>
+}
+
+impl GmmSubscriber {
+    /// Update the information from a message sent or received by the
+    /// subscriber.
+    pub fn update(&mut self, message: &GmmMessage) {
+        match message {
+            GmmMessage::AttachRequest { ms_network_capability, cksn } => {
+                self.supported_algorithms = ms_network_capability.supported_algorithms();
+                self.cksn = Some(*cksn);
+            },
+            GmmMessage::RoutingAreaUpdateRequest { cksn, ms_network_capability } => {
+                if let Some(ms_network_capability) = ms_network_capability {
+                    self.supported_algorithms = ms_network_capability.supported_algorithms();
+                }
+                self.cksn = Some(*cksn);
+            },
+            GmmMessage::AuthenticationAndCipheringRequest { ciphering_algorithm: value, rand, cksn, .. } => {
+                self.algorithm = ciphering_algorithm(*value);
+                if rand.is_some() {
+                    self.rand = *rand;
+                }
+                if cksn.is_some() {
+                    self.cksn = *cksn;
+                }
+            },
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : kasumi.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  KASUMI block cipher (3GPP TS 35.202), and the KGCORE keystream
+//  generation function built on it (3GPP TS 55.216)
+//-----------------------------------------------------------------------------/
+
+
+/// S7 substitution box.
+const S7: [u16; 128] = [
+    54, 50, 62, 56, 22, 34, 94, 96, 38, 6, 63, 93, 2, 18, 123, 33,
+    55, 113, 39, 114, 21, 67, 65, 12, 47, 73, 46, 27, 25, 111, 124, 81,
+    53, 9, 121, 79, 52, 60, 58, 48, 101, 127, 40, 120, 104, 70, 71, 43,
+    20, 122, 72, 61, 23, 109, 13, 100, 77, 1, 16, 7, 82, 10, 105, 98,
+    117, 116, 76, 11, 89, 106, 0, 125, 118, 99, 86, 69, 30, 57, 126, 87,
+    112, 51, 17, 5, 95, 14, 90, 84, 91, 8, 35, 103, 32, 97, 28, 66,
+    102, 31, 26, 45, 75, 4, 85, 92, 37, 74, 80, 49, 68, 29, 115, 44,
+    64, 107, 108, 24, 110, 83, 36, 78, 42, 19, 15, 41, 88, 119, 59, 3
+];
+
+/// S9 substitution box.
+const S9: [u16; 512] = [
+    167, 239, 161, 379, 391, 334, 9, 338, 38, 226, 48, 358, 452, 385, 90, 397,
+    183, 253, 147, 331, 415, 340, 51, 362, 306, 500, 262, 82, 216, 159, 356, 177,
+    175, 241, 489, 37, 206, 17, 0, 333, 44, 254, 378, 58, 143, 220, 81, 400,
+    95, 3, 315, 245, 54, 235, 218, 405, 472, 264, 172, 494, 371, 290, 399, 76,
+    165, 197, 395, 121, 257, 480, 423, 212, 240, 28, 462, 176, 406, 507, 288, 223,
+    501, 407, 249, 265, 89, 186, 221, 428, 164, 74, 440, 196, 458, 421, 350, 163,
+    232, 158, 134, 354, 13, 250, 491, 142, 191, 69, 193, 425, 152, 227, 366, 135,
+    344, 300, 276, 242, 437, 320, 113, 278, 11, 243, 87, 317, 36, 93, 496, 27,
+    487, 446, 482, 41, 68, 156, 457, 131, 326, 403, 339, 20, 39, 115, 442, 124,
+    475, 384, 508, 53, 112, 170, 479, 151, 126, 169, 73, 268, 279, 321, 168, 364,
+    363, 292, 46, 499, 393, 327, 324, 24, 456, 267, 157, 460, 488, 426, 309, 229,
+    439, 506, 208, 271, 349, 401, 434, 236, 16, 209, 359, 52, 56, 120, 199, 277,
+    465, 416, 252, 287, 246, 6, 83, 305, 420, 345, 153, 502, 65, 61, 244, 282,
+    173, 222, 418, 67, 386, 368, 261, 101, 476, 291, 195, 430, 49, 79, 166, 330,
+    280, 383, 373, 128, 382, 408, 155, 495, 367, 388, 274, 107, 459, 417, 62, 454,
+    132, 225, 203, 316, 234, 14, 301, 91, 503, 286, 424, 211, 347, 307, 140, 374,
+    35, 103, 125, 427, 19, 214, 453, 146, 498, 314, 444, 230, 256, 329, 198, 285,
+    50, 116, 78, 410, 10, 205, 510, 171, 231, 45, 139, 467, 29, 86, 505, 32,
+    72, 26, 342, 150, 313, 490, 431, 238, 411, 325, 149, 473, 40, 119, 174, 355,
+    185, 233, 389, 71, 448, 273, 372, 55, 110, 178, 322, 12, 469, 392, 369, 190,
+    1, 109, 375, 137, 181, 88, 75, 308, 260, 484, 98, 272, 370, 275, 412, 111,
+    336, 318, 4, 504, 492, 259, 304, 77, 337, 435, 21, 357, 303, 332, 483, 18,
+    47, 85, 25, 497, 474, 289, 100, 269, 296, 478, 270, 106, 31, 104, 433, 84,
+    414, 486, 394, 96, 99, 154, 511, 148, 413, 361, 409, 255, 162, 215, 302, 201,
+    266, 351, 343, 144, 441, 365, 108, 298, 251, 34, 182, 509, 138, 210, 335, 133,
+    311, 352, 328, 141, 396, 346, 123, 319, 450, 281, 429, 228, 443, 481, 92, 404,
+    485, 422, 248, 297, 23, 213, 130, 466, 22, 217, 283, 70, 294, 360, 419, 127,
+    312, 377, 7, 468, 194, 2, 117, 295, 463, 258, 224, 447, 247, 187, 80, 398,
+    284, 353, 105, 390, 299, 471, 470, 184, 57, 200, 348, 63, 204, 188, 33, 451,
+    97, 30, 310, 219, 94, 160, 129, 493, 64, 179, 263, 102, 189, 207, 114, 402,
+    438, 477, 387, 122, 192, 42, 381, 5, 145, 118, 180, 449, 293, 323, 136, 380,
+    43, 66, 60, 455, 341, 445, 202, 432, 8, 237, 15, 376, 436, 464, 59, 461
+];
+
+/// Constants xored with the key for deriving the modified key K'.
+const KEY_CONSTANTS: [u16; 8] = [0x0123, 0x4567, 0x89ab, 0xcdef, 0xfedc, 0xba98, 0x7654, 0x3210];
+
+/// Key modifier of KGCORE, xored with the key for the first encryption.
+const KEY_MODIFIER: u128 = 0x55555555555555555555555555555555;
+
+/// Subkeys of a KASUMI round.
+#[derive(Clone, Copy, Default)]
+struct RoundKeys {
+    kl: [u16; 2],
+    ko: [u16; 3],
+    ki: [u16; 3]
+}
+
+/// KASUMI block cipher, with its key schedule computed.
+#[derive(Clone)]
+pub struct Kasumi {
+    round_keys: [RoundKeys; 8]
+}
+
+impl Kasumi {
+    /// Key schedule, from a 128-bit key (whose most significant 16 bits
+    /// form the first subkey K1).
+    pub fn new(key: u128) -> Self {
+        let mut subkeys = [0u16; 8];
+        for (position, subkey) in subkeys.iter_mut().enumerate() {
+            *subkey = (key >> (112 - 16 * position)) as u16;
+        }
+        let mut modified_subkeys = [0u16; 8];
+        for (position, subkey) in modified_subkeys.iter_mut().enumerate() {
+            *subkey = subkeys[position] ^ KEY_CONSTANTS[position];
+        }
+        
+        let mut round_keys = [RoundKeys::default(); 8];
+        for (round, keys) in round_keys.iter_mut().enumerate() {
+            keys.kl = [subkeys[round].rotate_left(1), modified_subkeys[(round + 2) % 8]];
+            keys.ko = [subkeys[(round + 1) % 8].rotate_left(5), subkeys[(round + 5) % 8].rotate_left(8),
+                subkeys[(round + 6) % 8].rotate_left(13)];
+            keys.ki = [modified_subkeys[(round + 4) % 8], modified_subkeys[(round + 3) % 8],
+                modified_subkeys[(round + 7) % 8]];
+        }
+        Self { round_keys }
+    }
+    
+    /// Encrypt a 64-bit block. Odd rounds (starting from the first one)
+    /// apply FL then FO, even rounds FO then FL.
+    pub fn encrypt_block(&self, block: u64) -> u64 {
+        let (mut left, mut right) = ((block >> 32) as u32, block as u32);
+        for (round, keys) in self.round_keys.iter().enumerate() {
+            let output = if round % 2 == 0 {
+                fo_function(fl_function(left, keys), keys)
+            } else {
+                fl_function(fo_function(left, keys), keys)
+            };
+            let new_left = right ^ output;
+            right = left;
+            left = new_left;
+        }
+        ((left as u64) << 32) | right as u64
+    }
+}
+
+fn fl_function(input: u32, keys: &RoundKeys) -> u32 {
+    let (mut left, mut right) = ((input >> 16) as u16, input as u16);
+    right ^= (left & keys.kl[0]).rotate_left(1);
+    left ^= (right | keys.kl[1]).rotate_left(1);
+    ((left as u32) << 16) | right as u32
+}
+
+fn fo_function(input: u32, keys: &RoundKeys) -> u32 {
+    let (mut left, mut right) = ((input >> 16) as u16, input as u16);
+    for stage in 0..3 {
+        let new_right = fi_function(left ^ keys.ko[stage], keys.ki[stage]) ^ right;
+        left = right;
+        right = new_right;
+    }
+    ((left as u32) << 16) | right as u32
+}
+
+fn fi_function(input: u16, subkey: u16) -> u16 {
+    let mut nine = input >> 7;
+    let mut seven = input & 0x7f;
+    nine = S9[nine as usize] ^ seven;
+    seven = S7[seven as usize] ^ (nine & 0x7f);
+    seven ^= subkey >> 9;
+    nine ^= subkey & 0x1ff;
+    nine = S9[nine as usize] ^ seven;
+    seven = S7[seven as usize] ^ (nine & 0x7f);
+    (seven << 9) | nine
+}
+
+/// KGCORE keystream generator, from which GEA3 and GEA4 are derived,
+/// producing 64-bit keystream blocks (most significant bit first).
+#[derive(Clone)]
+pub struct KgCore {
+    kasumi: Kasumi,
+    modified_register: u64,
+    block_counter: u64,
+    previous_block: u64
+}
+
+impl KgCore {
+    /// Initialize KGCORE from its CA (8 bits), CB (5 bits), CC (32 bits),
+    /// CD (1 bit) and CE (16 bits) parameters and its 128-bit key CK.
+    pub fn new(ca: u8, cb: u8, cc: u32, cd: bool, ce: u16, ck: u128) -> Self {
+        let register = ((cc as u64) << 32) | (((cb & 0x1f) as u64) << 27) | ((cd as u64) << 26) |
+            ((ca as u64) << 16) | ce as u64;
+        Self {
+            kasumi: Kasumi::new(ck),
+            modified_register: Kasumi::new(ck ^ KEY_MODIFIER).encrypt_block(register),
+            block_counter: 0,
+            previous_block: 0
+        }
+    }
+    
+    /// Output the next keystream block: KASUMI of the modified register,
+    /// xor'ed with the block counter and the previous block.
+    pub fn next_block(&mut self) -> u64 {
+        self.previous_block = self.kasumi.encrypt_block(self.modified_register ^ self.block_counter ^ self.previous_block);
+        self.block_counter += 1;
+        self.previous_block
+    }
+}
@@ -0,0 +1,139 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : key.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Typed ciphering key input for the GEA algorithms
+//-----------------------------------------------------------------------------/
+
+
+use std::fmt;
+use std::str::FromStr;
+
+/// 64-bit ciphering key Kc, as derived from a subscriber key by the A8
+/// algorithm (GSM, see "comp128") or the c3 conversion function (UMTS,
+/// see "milenage::c3"). Its octets are
+/// stored most significant first, as provided by SIM cards and core
+/// network logs, and it is passed to the algorithms as "Kc.into()".
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Kc(pub u64);
+
+impl Kc {
+    pub fn from_bytes(bytes: [u8; 8]) -> Self {
+        Self(u64::from_be_bytes(bytes))
+    }
+    
+    pub fn to_bytes(self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+}
+
+impl From<u64> for Kc {
+    fn from(kc: u64) -> Self {
+        Self(kc)
+    }
+}
+
+impl From<Kc> for u64 {
+    fn from(kc: Kc) -> Self {
+        kc.0
+    }
+}
+
+/// Keys of the algorithm selector are 128-bit wide, a Kc filling their
+/// least significant bits.
+impl From<Kc> for u128 {
+    fn from(kc: Kc) -> Self {
+        kc.0 as u128
+    }
+}
+
+impl fmt::Display for Kc {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:016x}", self.0)
+    }
+}
+
+/// 128-bit ciphering key Kc128, used by GEA4. It is derived from the
+/// UMTS keys CK and IK (see "milenage::kc128"), its octets being stored
+/// most significant first as well.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Kc128(pub u128);
+
+impl Kc128 {
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(u128::from_be_bytes(bytes))
+    }
+    
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+}
+
+impl From<u128> for Kc128 {
+    fn from(kc: u128) -> Self {
+        Self(kc)
+    }
+}
+
+impl From<Kc128> for u128 {
+    fn from(kc: Kc128) -> Self {
+        kc.0
+    }
+}
+
+impl fmt::Display for Kc128 {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:032x}", self.0)
+    }
+}
+
+/// Error returned when parsing a Kc which is not made of 16 hexadecimal
+/// digits.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct InvalidKc;
+
+impl fmt::Display for InvalidKc {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Kc must be made of 16 hexadecimal digits")
+    }
+}
+
+impl std::error::Error for InvalidKc {}
+
+impl FromStr for Kc {
+    type Err = InvalidKc;
+    
+    /// Parse a Kc from 16 hexadecimal digits, optionally prefixed with
+    /// "0x" and separated by spaces or colons.
+    fn from_str(text: &str) -> Result<Self, InvalidKc> {
+        let text = text.trim();
+        let digits: String = text.strip_prefix("0x").unwrap_or(text).chars()
+            .filter(|character| *character != ' ' && *character != ':')
+            .collect();
+        if digits.len() != 16 || !digits.chars().all(|character| character.is_ascii_hexdigit()) {
+            return Err(InvalidKc);
+        }
+        u64::from_str_radix(&digits, 16).map(Self).map_err(|_| InvalidKc)
+    }
+}
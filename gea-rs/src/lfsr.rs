@@ -29,6 +29,9 @@
 //-----------------------------------------------------------------------------/
 
 
+use crate::link_direction::LinkDirection;
+use crate::f_lookup_table::F_LOOKUP_TABLE;
+
 pub trait LinearFeedbackShiftRegister {
     /// Initially clock the LFSR using successively all the bits passed
     /// into the concerned input register, the lowest bit of the
@@ -54,3 +57,43 @@ pub trait LinearFeedbackShiftRegister {
     /// single bit from six bits of the register) over the current LFSR.
     fn f_function(&mut self) -> bool;
 }
+
+/// Recover the key from an initialized S (or W) register, given the IV
+/// and direction bit, through running the initialization backwards
+/// (as done in the cryptanalysis paper).
+///
+/// The register is described by its size, the positions of the bits
+/// used as inputs of its "f" function, and its number of mixing clocks.
+pub(crate) fn recover_initialization_key(state: u128, register_size: usize, f_inputs: [usize; 7],
+        mixing_clocks: usize, iv: u32, direction: LinkDirection) -> u64 {
+    let f_function = |state: u128| F_LOOKUP_TABLE[f_inputs.iter().enumerate()
+        .fold(0, |input, (input_pos, &bit_pos)| input | ((((state >> bit_pos) & 1) as usize) << input_pos))];
+    let clock = |state: u128, input_bit: u128| (state >> 1) |
+        (((state & 1) ^ (f_function(state) as u128) ^ input_bit) << (register_size - 1));
+    
+    // Undo the mixing clocks: all the bits of the previous state but
+    // the lowest one are known, and the f function does not use it.
+    let mut state = state;
+    for _num_clock in 0..mixing_clocks {
+        let shifted = (state << 1) & ((1 << register_size) - 1);
+        let lowest_bit = (state >> (register_size - 1)) ^ (f_function(shifted) as u128);
+        state = shifted | lowest_bit;
+    }
+    
+    // Once the key has been loaded, the n-th bit entered into the
+    // register lies at position (size - 64 + n): replay the key loading
+    // to recover the key bit which has been xor'ed into each of them.
+    let mut register = (0..32).fold(0, |register, bit_pos| clock(register, ((iv >> bit_pos) & 1) as u128));
+    register = clock(register, match direction {
+        LinkDirection::Uplink => 0,
+        LinkDirection::Downlink => 1
+    });
+    let mut key = 0;
+    for bit_pos in 0..64 {
+        let entered_bit = (state >> (register_size - 64 + bit_pos)) & 1;
+        let key_bit = entered_bit ^ (register & 1) ^ (f_function(register) as u128);
+        register = clock(register, key_bit);
+        key |= (key_bit as u64) << bit_pos;
+    }
+    key
+}
@@ -31,6 +31,7 @@
 
 /// This is used to represent the direction bit passed into the
 /// initialization state for the S or W register.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum LinkDirection {
     Uplink,
     Downlink
@@ -0,0 +1,108 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : ciphering.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  LLC frame ciphering with GEA, as specified by 3GPP TS 44.064
+//  (annex A): computation of the GEA input from the ciphering parameters
+//-----------------------------------------------------------------------------/
+
+
+use crate::link_direction::LinkDirection;
+use crate::gea_algorithm::{GEAAlgorithm, InvalidKeySize};
+
+/// Kind of LLC frame, which determines how the GEA input is computed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LlcFrameKind {
+    /// UI frame, sent in unacknowledged operation, numbered by N(U).
+    Unacknowledged,
+    
+    /// I frame, sent in acknowledged operation, numbered by N(S).
+    Acknowledged
+}
+
+/// Parameters from which the 32-bit GEA input ("IV") of an LLC frame
+/// is computed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LlcCipheringParameters {
+    pub frame_kind: LlcFrameKind,
+    
+    /// Service Access Point Identifier of the LLE (4 bits).
+    pub sapi: u8,
+    
+    /// LLC Frame Number: N(U) for UI frames, N(S) for I frames (9 bits).
+    pub lfn: u16,
+    
+    /// Overflow counter, incremented by 512 each time the LFN wraps.
+    pub overflow_counter: u32,
+    
+    /// IOV-UI for UI frames, or the IOV-I of the LLE for I frames.
+    pub iov: u32
+}
+
+impl LlcCipheringParameters {
+    /// Compute the GEA input as specified by 3GPP TS 44.064 (A.2.1):
+    ///
+    /// - for UI frames: Input = ((IOV-UI xor SX) + LFN + OC) modulo 2^32,
+    ///   where SX = 2^27 * SAPI + 2^31,
+    /// - for I frames: Input = (IOV-I + LFN + OC) modulo 2^32.
+    pub fn gea_input(&self) -> u32 {
+        let base = match self.frame_kind {
+            LlcFrameKind::Unacknowledged => self.iov ^ (((self.sapi as u32 & 0xf) << 27) | (1 << 31)),
+            LlcFrameKind::Acknowledged => self.iov
+        };
+        base.wrapping_add((self.lfn & 0x1ff) as u32).wrapping_add(self.overflow_counter)
+    }
+}
+
+/// Ciphering context of an LLC connection: algorithm, Kc and direction
+/// (uplink for frames sent by the MS, downlink for frames sent by the
+/// SGSN). The Kc is a 128-bit Kc128 for GEA-4.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LlcCipher {
+    pub algorithm: GEAAlgorithm,
+    pub kc: u128,
+    pub direction: LinkDirection
+}
+
+impl LlcCipher {
+    pub fn new(algorithm: GEAAlgorithm, kc: u128, direction: LinkDirection) -> Self {
+        Self { algorithm, kc, direction }
+    }
+    
+    /// Encrypt or decrypt the ciphered part of an LLC frame, i.e. its
+    /// information field followed by its FCS.
+    pub fn crypt_payload(&self, parameters: &LlcCipheringParameters, payload: &[u8]) -> Result<Vec<u8>, InvalidKeySize> {
+        self.algorithm.crypt_stream(self.kc, parameters.gea_input(), self.direction, payload)
+    }
+    
+    /// Encrypt or decrypt a whole LLC frame, whose address and control
+    /// fields (which are never ciphered) span "header_length" bytes.
+    pub fn crypt_frame(&self, parameters: &LlcCipheringParameters, frame: &[u8], header_length: usize)
+            -> Result<Vec<u8>, InvalidKeySize> {
+        let header_length = header_length.min(frame.len());
+        let mut output = frame[..header_length].to_vec();
+        output.extend(self.crypt_payload(parameters, &frame[header_length..])?);
+        Ok(output)
+    }
+}
@@ -0,0 +1,413 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : frame.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  LLC frame model (3GPP TS 44.064): parsing and serialization of the
+//  address and control fields, and computation of the 24-bit FCS
+//-----------------------------------------------------------------------------/
+
+
+use std::fmt;
+
+use crate::gea_algorithm::InvalidKeySize;
+use crate::llc::ciphering::{LlcCipher, LlcCipheringParameters, LlcFrameKind};
+
+/// Length of the FCS field, at the end of each LLC frame.
+pub const FCS_LENGTH: usize = 3;
+
+/// Number of information field octets protected by the FCS of an UI
+/// frame sent in unprotected mode (N202).
+pub const N202: usize = 4;
+
+/// Generator polynomial of the FCS, in reflected form.
+const FCS_POLYNOMIAL: u32 = 0xad85dd;
+
+/// Error encountered when parsing an LLC frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LlcError {
+    /// The frame is shorter than its header and FCS.
+    TooShort,
+    
+    /// The protocol discriminator bit of the address field is set.
+    InvalidProtocolDiscriminator,
+    
+    /// The FCS does not match the contents of the frame, e.g. when
+    /// the frame has been deciphered with a wrong key.
+    InvalidFcs,
+    
+    /// An XID parameter is truncated, has an invalid length for its
+    /// type, or is too long to be encoded (over 255 octets).
+    InvalidXidParameter,
+    
+    /// The Kc is wider than the key size of the ciphering algorithm.
+    InvalidKeySize
+}
+
+impl fmt::Display for LlcError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            LlcError::TooShort => "LLC frame too short",
+            LlcError::InvalidProtocolDiscriminator => "invalid LLC protocol discriminator",
+            LlcError::InvalidFcs => "invalid LLC FCS",
+            LlcError::InvalidXidParameter => "invalid LLC XID parameter",
+            LlcError::InvalidKeySize => "Kc too wide for the LLC ciphering algorithm"
+        })
+    }
+}
+
+impl std::error::Error for LlcError {}
+
+impl From<InvalidKeySize> for LlcError {
+    fn from(_error: InvalidKeySize) -> Self {
+        LlcError::InvalidKeySize
+    }
+}
+
+/// Supervisory function bits (S1, S2) of I and S frames.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SupervisoryFunction {
+    ReceiveReady,
+    Acknowledgement,
+    ReceiveNotReady,
+    SelectiveAcknowledgement
+}
+
+impl SupervisoryFunction {
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0 => SupervisoryFunction::ReceiveReady,
+            1 => SupervisoryFunction::Acknowledgement,
+            2 => SupervisoryFunction::ReceiveNotReady,
+            _ => SupervisoryFunction::SelectiveAcknowledgement
+        }
+    }
+    
+    fn to_bits(self) -> u8 {
+        match self {
+            SupervisoryFunction::ReceiveReady => 0,
+            SupervisoryFunction::Acknowledgement => 1,
+            SupervisoryFunction::ReceiveNotReady => 2,
+            SupervisoryFunction::SelectiveAcknowledgement => 3
+        }
+    }
+}
+
+/// Command or response of U frames (M4 to M1 bits).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UnnumberedFunction {
+    Null,
+    DisconnectedMode,
+    Disconnect,
+    UnnumberedAcknowledgement,
+    SetAsynchronousBalancedMode,
+    FrameReject,
+    ExchangeIdentification,
+    Unknown(u8)
+}
+
+impl UnnumberedFunction {
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0xf {
+            0b0000 => UnnumberedFunction::Null,
+            0b0001 => UnnumberedFunction::DisconnectedMode,
+            0b0100 => UnnumberedFunction::Disconnect,
+            0b0110 => UnnumberedFunction::UnnumberedAcknowledgement,
+            0b0111 => UnnumberedFunction::SetAsynchronousBalancedMode,
+            0b1000 => UnnumberedFunction::FrameReject,
+            0b1011 => UnnumberedFunction::ExchangeIdentification,
+            bits => UnnumberedFunction::Unknown(bits)
+        }
+    }
+    
+    fn to_bits(self) -> u8 {
+        match self {
+            UnnumberedFunction::Null => 0b0000,
+            UnnumberedFunction::DisconnectedMode => 0b0001,
+            UnnumberedFunction::Disconnect => 0b0100,
+            UnnumberedFunction::UnnumberedAcknowledgement => 0b0110,
+            UnnumberedFunction::SetAsynchronousBalancedMode => 0b0111,
+            UnnumberedFunction::FrameReject => 0b1000,
+            UnnumberedFunction::ExchangeIdentification => 0b1011,
+            UnnumberedFunction::Unknown(bits) => bits & 0xf
+        }
+    }
+}
+
+/// Control field of an LLC frame.
+#[derive(Clone, PartialEq, Debug)]
+pub enum LlcControl {
+    /// I frame (with supervisory information), carrying acknowledged
+    /// data.
+    Information {
+        ack_request: bool,
+        ns: u16,
+        nr: u16,
+        function: SupervisoryFunction,
+        /// Bitmap of the SACK function (empty for other functions).
+        sack_bitmap: Vec<u8>
+    },
+    
+    /// S frame.
+    Supervisory {
+        ack_request: bool,
+        nr: u16,
+        function: SupervisoryFunction,
+        /// Bitmap of the SACK function (empty for other functions).
+        sack_bitmap: Vec<u8>
+    },
+    
+    /// UI frame, carrying unacknowledged data.
+    Unconfirmed {
+        nu: u16,
+        /// E bit: the information field and FCS are ciphered.
+        encrypted: bool,
+        /// PM bit: the FCS covers the whole information field.
+        protected: bool
+    },
+    
+    /// U frame.
+    Unnumbered {
+        poll_final: bool,
+        function: UnnumberedFunction
+    }
+}
+
+/// LLC frame, whose information field is in clear.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LlcFrame {
+    /// C/R bit of the address field.
+    pub command_response: bool,
+    
+    /// Service Access Point Identifier (4 bits).
+    pub sapi: u8,
+    
+    pub control: LlcControl,
+    pub information: Vec<u8>
+}
+
+impl LlcFrame {
+    /// Parse the address and control fields of a frame, returning them
+    /// together with the header length. The frame is expected to end
+    /// with its FCS, which is required for finding the end of the SACK
+    /// bitmap of S frames.
+    pub fn parse_header(data: &[u8]) -> Result<(bool, u8, LlcControl, usize), LlcError> {
+        if data.len() < 2 + FCS_LENGTH {
+            return Err(LlcError::TooShort);
+        }
+        if data[0] & 0x80 != 0 {
+            return Err(LlcError::InvalidProtocolDiscriminator);
+        }
+        let command_response = data[0] & 0x40 != 0;
+        let sapi = data[0] & 0x0f;
+        let control = &data[1..data.len() - FCS_LENGTH];
+        
+        let (control, control_length) = if control[0] & 0x80 == 0 {
+            if control.len() < 3 {
+                return Err(LlcError::TooShort);
+            }
+            let function = SupervisoryFunction::from_bits(control[2]);
+            let mut control_length = 3;
+            let mut sack_bitmap = vec![];
+            if function == SupervisoryFunction::SelectiveAcknowledgement {
+                let bitmap_length = (control.get(3).ok_or(LlcError::TooShort)? & 0x1f) as usize + 1;
+                sack_bitmap = control.get(4..4 + bitmap_length).ok_or(LlcError::TooShort)?.to_vec();
+                control_length += 1 + bitmap_length;
+            }
+            (LlcControl::Information {
+                ack_request: control[0] & 0x40 != 0,
+                ns: (((control[0] & 0x1f) as u16) << 4) | (control[1] >> 4) as u16,
+                nr: (((control[1] & 0x07) as u16) << 6) | (control[2] >> 2) as u16,
+                function,
+                sack_bitmap
+            }, control_length)
+        }
+        else if control[0] & 0xc0 == 0x80 {
+            let function = SupervisoryFunction::from_bits(control[1]);
+            let sack_bitmap = if function == SupervisoryFunction::SelectiveAcknowledgement {
+                control[2..].to_vec()
+            } else {
+                vec![]
+            };
+            let control_length = 2 + sack_bitmap.len();
+            (LlcControl::Supervisory {
+                ack_request: control[0] & 0x20 != 0,
+                nr: (((control[0] & 0x07) as u16) << 6) | (control[1] >> 2) as u16,
+                function,
+                sack_bitmap
+            }, control_length)
+        }
+        else if control[0] & 0xe0 == 0xc0 {
+            (LlcControl::Unconfirmed {
+                nu: (((control[0] & 0x07) as u16) << 6) | (control[1] >> 2) as u16,
+                encrypted: control[1] & 0x02 != 0,
+                protected: control[1] & 0x01 != 0
+            }, 2)
+        }
+        else {
+            (LlcControl::Unnumbered {
+                poll_final: control[0] & 0x10 != 0,
+                function: UnnumberedFunction::from_bits(control[0])
+            }, 1)
+        };
+        
+        Ok((command_response, sapi, control, 1 + control_length))
+    }
+    
+    /// Parse a frame whose information field is in clear, verifying
+    /// its FCS.
+    pub fn parse(data: &[u8]) -> Result<Self, LlcError> {
+        let (command_response, sapi, control, header_length) = Self::parse_header(data)?;
+        let frame = Self {
+            command_response,
+            sapi,
+            control,
+            information: data[header_length..data.len() - FCS_LENGTH].to_vec()
+        };
+        
+        let received_fcs = read_fcs(&data[data.len() - FCS_LENGTH..]);
+        if frame.compute_fcs(&data[..data.len() - FCS_LENGTH]) != received_fcs {
+            return Err(LlcError::InvalidFcs);
+        }
+        Ok(frame)
+    }
+    
+    /// Serialize the address and control fields of the frame.
+    ///
+    /// Panics if the SACK bitmap of an I frame is empty or longer than
+    /// 32 octets, as its length is encoded as K = length - 1 over 5 bits.
+    pub fn serialize_header(&self) -> Vec<u8> {
+        let mut header = vec![((self.command_response as u8) << 6) | (self.sapi & 0x0f)];
+        match &self.control {
+            LlcControl::Information { ack_request, ns, nr, function, sack_bitmap } => {
+                header.push(((*ack_request as u8) << 6) | ((ns >> 4) & 0x1f) as u8);
+                header.push((((ns & 0x0f) as u8) << 4) | ((nr >> 6) & 0x07) as u8);
+                header.push((((nr & 0x3f) as u8) << 2) | function.to_bits());
+                if *function == SupervisoryFunction::SelectiveAcknowledgement {
+                    assert!((1..=32).contains(&sack_bitmap.len()), "the SACK bitmap of an I frame holds 1 to 32 octets");
+                    header.push((sack_bitmap.len() - 1) as u8);
+                    header.extend_from_slice(sack_bitmap);
+                }
+            },
+            LlcControl::Supervisory { ack_request, nr, function, sack_bitmap } => {
+                header.push(0x80 | ((*ack_request as u8) << 5) | ((nr >> 6) & 0x07) as u8);
+                header.push((((nr & 0x3f) as u8) << 2) | function.to_bits());
+                if *function == SupervisoryFunction::SelectiveAcknowledgement {
+                    header.extend_from_slice(sack_bitmap);
+                }
+            },
+            LlcControl::Unconfirmed { nu, encrypted, protected } => {
+                header.push(0xc0 | ((nu >> 6) & 0x07) as u8);
+                header.push((((nu & 0x3f) as u8) << 2) | ((*encrypted as u8) << 1) | *protected as u8);
+            },
+            LlcControl::Unnumbered { poll_final, function } => {
+                header.push(0xe0 | ((*poll_final as u8) << 4) | function.to_bits());
+            }
+        }
+        header
+    }
+    
+    /// Serialize the frame, including its FCS, with its information
+    /// field in clear.
+    ///
+    /// Panics on an invalid SACK bitmap, as `serialize_header` does.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = self.serialize_header();
+        data.extend_from_slice(&self.information);
+        let fcs = self.compute_fcs(&data);
+        data.extend_from_slice(&fcs.to_le_bytes()[..FCS_LENGTH]);
+        data
+    }
+    
+    /// Compute the FCS of the frame from its serialized header and
+    /// information field: UI frames sent in unprotected mode only have
+    /// their header and first N202 information octets covered.
+    fn compute_fcs(&self, header_and_information: &[u8]) -> u32 {
+        match self.control {
+            LlcControl::Unconfirmed { protected: false, .. } => {
+                let header_length = header_and_information.len() - self.information.len();
+                fcs(&header_and_information[..(header_length + N202).min(header_and_information.len())])
+            },
+            _ => fcs(header_and_information)
+        }
+    }
+    
+    /// Whether the information field and FCS of the frame are ciphered:
+    /// this is the case for I frames, and for UI frames with the E bit
+    /// set.
+    pub fn is_ciphered(&self) -> bool {
+        matches!(self.control, LlcControl::Information { .. } | LlcControl::Unconfirmed { encrypted: true, .. })
+    }
+    
+    /// Parameters for computing the GEA input of the frame, given the IOV
+    /// (IOV-UI for UI frames, IOV-I of the LLE for I frames) and the
+    /// current overflow counter, if the frame is ciphered.
+    pub fn ciphering_parameters(&self, iov: u32, overflow_counter: u32) -> Option<LlcCipheringParameters> {
+        let (frame_kind, lfn) = match self.control {
+            LlcControl::Information { ns, .. } => (LlcFrameKind::Acknowledged, ns),
+            LlcControl::Unconfirmed { nu, encrypted: true, .. } => (LlcFrameKind::Unacknowledged, nu),
+            _ => return None
+        };
+        Some(LlcCipheringParameters { frame_kind, sapi: self.sapi, lfn, overflow_counter, iov })
+    }
+    
+    /// Serialize the frame, ciphering its information field and FCS if
+    /// required.
+    pub fn encrypt(&self, cipher: &LlcCipher, iov: u32, overflow_counter: u32) -> Result<Vec<u8>, LlcError> {
+        let data = self.serialize();
+        match self.ciphering_parameters(iov, overflow_counter) {
+            Some(parameters) => Ok(cipher.crypt_frame(&parameters, &data, data.len() - self.information.len() - FCS_LENGTH)?),
+            None => Ok(data)
+        }
+    }
+    
+    /// Decipher a received frame if required, then parse it and verify
+    /// its FCS, so that frames deciphered with wrong parameters are
+    /// rejected.
+    pub fn decrypt(data: &[u8], cipher: &LlcCipher, iov: u32, overflow_counter: u32) -> Result<Self, LlcError> {
+        let (command_response, sapi, control, header_length) = Self::parse_header(data)?;
+        let header = Self { command_response, sapi, control, information: vec![] };
+        match header.ciphering_parameters(iov, overflow_counter) {
+            Some(parameters) => Self::parse(&cipher.crypt_frame(&parameters, data, header_length)?),
+            None => Self::parse(data)
+        }
+    }
+}
+
+/// Compute the 24-bit FCS over some data (initialized with ones, and
+/// complemented at the end).
+pub fn fcs(data: &[u8]) -> u32 {
+    let mut register: u32 = 0xffffff;
+    for byte in data {
+        register ^= *byte as u32;
+        for _num_bit in 0..8 {
+            register = if register & 1 != 0 { (register >> 1) ^ FCS_POLYNOMIAL } else { register >> 1 };
+        }
+    }
+    !register & 0xffffff
+}
+
+/// Read a FCS field, which is transmitted lowest octet first.
+fn read_fcs(data: &[u8]) -> u32 {
+    data[0] as u32 | ((data[1] as u32) << 8) | ((data[2] as u32) << 16)
+}
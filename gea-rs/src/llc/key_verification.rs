@@ -0,0 +1,142 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : key_verification.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Verification of candidate keys against captured ciphered LLC frames,
+//  accepting only keys which decipher frames into valid FCS
+//-----------------------------------------------------------------------------/
+
+
+use crate::link_direction::LinkDirection;
+use crate::gea_algorithm::{GEAAlgorithm, LfsrAlgorithm};
+use crate::registers::s_register::SRegister;
+use crate::registers::w_register::WRegister;
+use crate::llc::ciphering::LlcCipher;
+use crate::llc::frame::LlcFrame;
+
+/// Ciphered LLC frame captured from the network, with the information
+/// required for deciphering it besides the key.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CapturedFrame {
+    pub data: Vec<u8>,
+    pub direction: LinkDirection,
+    
+    /// IOV-UI for UI frames, or IOV-I of the LLE for I frames.
+    pub iov: u32,
+    
+    pub overflow_counter: u32
+}
+
+impl CapturedFrame {
+    /// GEA input used for ciphering the frame, if it is ciphered.
+    pub fn gea_input(&self) -> Option<u32> {
+        let (command_response, sapi, control, _) = LlcFrame::parse_header(&self.data).ok()?;
+        let header = LlcFrame { command_response, sapi, control, information: vec![] };
+        header.ciphering_parameters(self.iov, self.overflow_counter)
+            .map(|parameters| parameters.gea_input())
+    }
+}
+
+/// Candidate key produced by an attack.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum KeyCandidate {
+    /// Candidate Kc.
+    Kc(u64),
+    
+    /// Candidate S (GEA-1) or W (GEA-2) register state, once initialized
+    /// for deciphering the captured frame at the given index (e.g.
+    /// recovered from the keystream of this frame).
+    InitRegister {
+        state: u128,
+        frame_index: usize
+    }
+}
+
+/// Verifier of candidate keys, against a set of captured frames.
+pub struct KeyVerifier {
+    pub algorithm: LfsrAlgorithm,
+    pub frames: Vec<CapturedFrame>,
+    
+    /// Number of ciphered frames which must be deciphered with a valid
+    /// FCS for accepting a key. Each frame gives around 24 bits of
+    /// confidence.
+    pub required_frames: usize
+}
+
+impl KeyVerifier {
+    pub fn new(algorithm: LfsrAlgorithm, frames: Vec<CapturedFrame>, required_frames: usize) -> Self {
+        Self { algorithm, frames, required_frames }
+    }
+    
+    /// Kc corresponding to a candidate: S or W register states are
+    /// converted through running their initialization backwards.
+    pub fn candidate_kc(&self, candidate: &KeyCandidate) -> Option<u64> {
+        match *candidate {
+            KeyCandidate::Kc(kc) => Some(kc),
+            KeyCandidate::InitRegister { state, frame_index } => {
+                let frame = self.frames.get(frame_index)?;
+                let iv = frame.gea_input()?;
+                match self.algorithm {
+                    LfsrAlgorithm::GEA1 => Some(SRegister(state).recover_key(iv, frame.direction)),
+                    LfsrAlgorithm::GEA2 => Some(WRegister(state).recover_key(iv, frame.direction)),
+                }
+            }
+        }
+    }
+    
+    /// Verify a Kc: it is accepted once the required number of ciphered
+    /// frames have been deciphered with a valid FCS, and rejected as soon
+    /// as a frame is deciphered with an invalid one (or when there are
+    /// not enough ciphered frames).
+    pub fn verify(&self, kc: u64) -> bool {
+        let mut valid_frames = 0;
+        for frame in &self.frames {
+            if valid_frames >= self.required_frames {
+                break;
+            }
+            if frame.gea_input().is_none() {
+                continue;
+            }
+            let cipher = LlcCipher::new(GEAAlgorithm::from(self.algorithm), kc.into(), frame.direction);
+            if LlcFrame::decrypt(&frame.data, &cipher, frame.iov, frame.overflow_counter).is_err() {
+                return false;
+            }
+            valid_frames += 1;
+        }
+        valid_frames >= self.required_frames
+    }
+    
+    /// Keep the Kc of the candidates which are accepted.
+    pub fn filter_candidates<I: IntoIterator<Item = KeyCandidate>>(&self, candidates: I) -> Vec<u64> {
+        let mut accepted: Vec<u64> = vec![];
+        for candidate in candidates {
+            if let Some(kc) = self.candidate_kc(&candidate) {
+                if !accepted.contains(&kc) && self.verify(kc) {
+                    accepted.push(kc);
+                }
+            }
+        }
+        accepted
+    }
+}
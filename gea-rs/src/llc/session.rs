@@ -0,0 +1,316 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : session.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Tracking of the LLC ciphering state of a GPRS session: overflow
+//  counters of each LLE as frame numbers wrap, and IOV values
+//-----------------------------------------------------------------------------/
+
+
+use std::collections::HashMap;
+
+use crate::link_direction::LinkDirection;
+use crate::gea_algorithm::{GEAAlgorithm, InvalidKeySize};
+use crate::gmm::{GMM_SAPI, GmmMessage, GmmSubscriber};
+use crate::llc::ciphering::{LlcCipher, LlcCipheringParameters};
+use crate::llc::frame::{LlcControl, LlcError, LlcFrame, UnnumberedFunction};
+use crate::llc::xid::XidParameter;
+
+/// Modulus of the LLC frame numbers N(U) and N(S) (9 bits).
+pub const LFN_MODULUS: u16 = 512;
+
+/// Default number of frame numbers, behind the latest frame seen, within
+/// which frames are considered as reordered or retransmitted rather than
+/// coming after a wrap of the frame number.
+pub const DEFAULT_REORDERING_WINDOW: u16 = 64;
+
+/// Identifier of an LLE, and of the direction of the frames it sends.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LleIdentifier {
+    pub tlli: u32,
+    pub sapi: u8,
+    pub direction: LinkDirection
+}
+
+/// Overflow counter for a sequence of frame numbers (N(U) or N(S)).
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct OverflowCounter {
+    /// Overflow counter of the latest frame seen.
+    pub value: u32,
+    
+    /// Frame number of the latest frame seen.
+    pub latest_lfn: Option<u16>
+}
+
+impl OverflowCounter {
+    /// Overflow counter to be used for a received frame number, updating
+    /// the counter state if the frame comes after the latest one.
+    /// Frames up to "reordering_window" numbers behind the latest one are
+    /// assumed to be late, while other frame numbers are assumed to come
+    /// after it (possibly with some frames lost in between).
+    pub fn update(&mut self, lfn: u16, reordering_window: u16) -> u32 {
+        let lfn = lfn % LFN_MODULUS;
+        let latest_lfn = match self.latest_lfn {
+            Some(latest_lfn) => latest_lfn,
+            None => {
+                self.latest_lfn = Some(lfn);
+                return self.value;
+            }
+        };
+        let distance = (lfn + LFN_MODULUS - latest_lfn) % LFN_MODULUS;
+        
+        if distance == 0 || distance >= LFN_MODULUS - reordering_window {
+            // Retransmitted or late frame, sent before a wrap if its
+            // number is higher (no wrap precedes the first frames)
+            if lfn > latest_lfn {
+                self.value.saturating_sub(LFN_MODULUS as u32)
+            } else {
+                self.value
+            }
+        }
+        else {
+            if lfn < latest_lfn {
+                self.value = self.value.wrapping_add(LFN_MODULUS as u32);
+            }
+            self.latest_lfn = Some(lfn);
+            self.value
+        }
+    }
+}
+
+/// Ciphering state of an LLE in a direction.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct LleState {
+    /// Overflow counter of UI frames.
+    pub unacknowledged: OverflowCounter,
+    
+    /// Overflow counter of I frames.
+    pub acknowledged: OverflowCounter
+}
+
+/// Ciphering context of a frame processed within a session: the GEA
+/// instance to use for it, if it is ciphered.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FrameCiphering {
+    pub cipher: LlcCipher,
+    pub parameters: Option<LlcCipheringParameters>
+}
+
+impl FrameCiphering {
+    /// GEA input of the frame, if it is ciphered.
+    pub fn gea_input(&self) -> Option<u32> {
+        self.parameters.map(|parameters| parameters.gea_input())
+    }
+    
+    /// Keystream used for ciphering the frame, if it is ciphered.
+    pub fn keystream(&self, num_bytes: usize) -> Result<Option<Vec<u8>>, InvalidKeySize> {
+        self.gea_input().map(|iv| {
+            self.cipher.algorithm.generate_stream(self.cipher.kc, iv, self.cipher.direction, num_bytes)
+        }).transpose()
+    }
+    
+    /// Decipher the frame if required, and parse it.
+    pub fn decrypt(&self, data: &[u8]) -> Result<LlcFrame, LlcError> {
+        match self.parameters {
+            Some(parameters) => LlcFrame::decrypt(data, &self.cipher, parameters.iov, parameters.overflow_counter),
+            None => LlcFrame::parse(data)
+        }
+    }
+}
+
+/// LLC ciphering state of a GPRS session, made of the logical links of
+/// one or more MSs, each identified by its TLLI. Frames are to be
+/// processed in the order they were captured.
+pub struct LlcSession {
+    /// Algorithm and Kc used for logical links with no specific ones.
+    pub algorithm: GEAAlgorithm,
+    pub kc: u128,
+    
+    pub reordering_window: u16,
+    
+    ciphering: HashMap<u32, (GEAAlgorithm, u128)>,
+    iov_ui: HashMap<u32, u32>,
+    iov_i: HashMap<(u32, u8), u32>,
+    lles: HashMap<LleIdentifier, LleState>,
+    subscribers: HashMap<u32, GmmSubscriber>
+}
+
+impl LlcSession {
+    pub fn new(algorithm: GEAAlgorithm, kc: u128) -> Self {
+        Self {
+            algorithm,
+            kc,
+            reordering_window: DEFAULT_REORDERING_WINDOW,
+            ciphering: HashMap::new(),
+            iov_ui: HashMap::new(),
+            iov_i: HashMap::new(),
+            lles: HashMap::new(),
+            subscribers: HashMap::new()
+        }
+    }
+    
+    /// Set the algorithm and Kc of a logical link.
+    pub fn set_ciphering(&mut self, tlli: u32, algorithm: GEAAlgorithm, kc: u128) {
+        self.ciphering.insert(tlli, (algorithm, kc));
+    }
+    
+    /// Algorithm and Kc of a logical link.
+    pub fn ciphering(&self, tlli: u32) -> (GEAAlgorithm, u128) {
+        self.ciphering.get(&tlli).copied().unwrap_or((self.algorithm, self.kc))
+    }
+    
+    /// Set the IOV-UI of a logical link (common to all its SAPIs).
+    pub fn set_iov_ui(&mut self, tlli: u32, iov: u32) {
+        self.iov_ui.insert(tlli, iov);
+    }
+    
+    /// IOV-UI of a logical link (zero until negotiated).
+    pub fn iov_ui(&self, tlli: u32) -> u32 {
+        self.iov_ui.get(&tlli).copied().unwrap_or(0)
+    }
+    
+    /// Set the IOV-I of an LLE.
+    pub fn set_iov_i(&mut self, tlli: u32, sapi: u8, iov: u32) {
+        self.iov_i.insert((tlli, sapi), iov);
+    }
+    
+    /// IOV-I of an LLE (zero until negotiated).
+    pub fn iov_i(&self, tlli: u32, sapi: u8) -> u32 {
+        self.iov_i.get(&(tlli, sapi)).copied().unwrap_or(0)
+    }
+    
+    /// Ciphering information gathered from the GMM messages of a logical
+    /// link, if any were seen.
+    pub fn subscriber(&self, tlli: u32) -> Option<&GmmSubscriber> {
+        self.subscribers.get(&tlli)
+    }
+    
+    /// Ciphering state of an LLE in a direction, if frames were seen.
+    pub fn lle_state(&self, lle: &LleIdentifier) -> Option<&LleState> {
+        self.lles.get(lle)
+    }
+    
+    /// Process the next captured frame of a logical link, sent in the
+    /// given direction, returning how to decipher it. IOVs negotiated
+    /// through XID parameters of U frames are picked up automatically.
+    pub fn process_frame(&mut self, tlli: u32, direction: LinkDirection, data: &[u8]) -> Result<FrameCiphering, LlcError> {
+        let (command_response, sapi, control, _) = LlcFrame::parse_header(data)?;
+        let header = LlcFrame { command_response, sapi, control, information: vec![] };
+        let (algorithm, kc) = self.ciphering(tlli);
+        let cipher = LlcCipher::new(algorithm, kc, direction);
+        
+        let lle = LleIdentifier { tlli, sapi, direction };
+        let reordering_window = self.reordering_window;
+        let overflow_counter = match header.control {
+            LlcControl::Information { ns, .. } => {
+                Some(self.lles.entry(lle).or_default().acknowledged.update(ns, reordering_window))
+            },
+            LlcControl::Unconfirmed { nu, .. } => {
+                Some(self.lles.entry(lle).or_default().unacknowledged.update(nu, reordering_window))
+            },
+            LlcControl::Unnumbered { function: UnnumberedFunction::SetAsynchronousBalancedMode, .. } |
+            LlcControl::Unnumbered { function: UnnumberedFunction::UnnumberedAcknowledgement, .. } => {
+                // (Re-)establishment of acknowledged operation resets the
+                // I frame numbering in both directions
+                self.reset_acknowledged(tlli, sapi);
+                self.apply_xid_parameters(tlli, sapi, data);
+                None
+            },
+            LlcControl::Unnumbered { function: UnnumberedFunction::ExchangeIdentification, .. } => {
+                self.apply_xid_parameters(tlli, sapi, data);
+                None
+            },
+            _ => None
+        };
+        
+        let parameters = overflow_counter.and_then(|overflow_counter| {
+            let iov = match header.control {
+                LlcControl::Information { .. } => self.iov_i(tlli, sapi),
+                _ => self.iov_ui(tlli)
+            };
+            header.ciphering_parameters(iov, overflow_counter)
+        });
+        let ciphering = FrameCiphering { cipher, parameters };
+        if sapi == GMM_SAPI {
+            self.apply_gmm_message(tlli, &ciphering, data);
+        }
+        Ok(ciphering)
+    }
+    
+    /// Process the next captured frame of a logical link, and decipher it.
+    pub fn decrypt_frame(&mut self, tlli: u32, direction: LinkDirection, data: &[u8]) -> Result<LlcFrame, LlcError> {
+        self.process_frame(tlli, direction, data)?.decrypt(data)
+    }
+    
+    /// Pick up the IOVs, and resets, from the XID parameters carried by a
+    /// U frame. Frames with an invalid FCS or parameters are ignored.
+    fn apply_xid_parameters(&mut self, tlli: u32, sapi: u8, data: &[u8]) {
+        let parameters = match LlcFrame::parse(data).and_then(|frame| XidParameter::parse_list(&frame.information)) {
+            Ok(parameters) => parameters,
+            Err(_) => return
+        };
+        // A reset applies before the other parameters of the same frame
+        if parameters.contains(&XidParameter::Reset) {
+            self.iov_ui.remove(&tlli);
+            self.iov_i.retain(|(lle_tlli, _), _| *lle_tlli != tlli);
+            self.lles.retain(|lle, _| lle.tlli != tlli);
+        }
+        for parameter in parameters {
+            match parameter {
+                XidParameter::IovUi(iov) => self.set_iov_ui(tlli, iov),
+                XidParameter::IovI(iov) => self.set_iov_i(tlli, sapi, iov),
+                _ => {}
+            }
+        }
+    }
+    
+    /// Pick up the ciphering information of a GMM message: the algorithm
+    /// selected by an Authentication and Ciphering Request applies to the
+    /// following frames of the logical link, with the Kc already set for
+    /// it. Frames which cannot be deciphered or parsed are ignored.
+    fn apply_gmm_message(&mut self, tlli: u32, ciphering: &FrameCiphering, data: &[u8]) {
+        let message = match ciphering.decrypt(data) {
+            Ok(frame) if !frame.information.is_empty() => match GmmMessage::parse(&frame.information) {
+                Ok(message) => message,
+                Err(_) => return
+            },
+            _ => return
+        };
+        let subscriber = self.subscribers.entry(tlli).or_default();
+        subscriber.update(&message);
+        if let (GmmMessage::AuthenticationAndCipheringRequest { .. }, Some(algorithm)) = (&message, subscriber.algorithm) {
+            let (_, kc) = self.ciphering(tlli);
+            self.set_ciphering(tlli, algorithm, kc);
+        }
+    }
+    
+    /// Reset the I frame overflow counters of an LLE.
+    fn reset_acknowledged(&mut self, tlli: u32, sapi: u8) {
+        for direction in [LinkDirection::Uplink, LinkDirection::Downlink] {
+            if let Some(state) = self.lles.get_mut(&LleIdentifier { tlli, sapi, direction }) {
+                state.acknowledged = OverflowCounter::default();
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : xid.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  LLC XID parameters, as specified by 3GPP TS 44.064 (6.4.1.6 and 8.9):
+//  parsing and encoding of the information field of XID, SABM and UA
+//  frames
+//-----------------------------------------------------------------------------/
+
+
+use crate::llc::frame::LlcError;
+
+/// LLC layer parameter negotiated through XID. Integer values are
+/// transmitted most significant octet first.
+#[derive(Clone, PartialEq, Debug)]
+pub enum XidParameter {
+    /// LLC version number (type 0).
+    Version(u8),
+    
+    /// Input offset value for UI frames, common to all SAPIs (type 1).
+    IovUi(u32),
+    
+    /// Input offset value for I frames of the SAPI (type 2).
+    IovI(u32),
+    
+    /// Retransmission timer, in units of 0.1 s (type 3).
+    T200(u16),
+    
+    /// Maximum number of retransmissions (type 4).
+    N200(u8),
+    
+    /// Maximum number of octets in the information field of U and UI
+    /// frames (type 5).
+    N201U(u16),
+    
+    /// Maximum number of octets in the information field of I frames
+    /// (type 6).
+    N201I(u16),
+    
+    /// I frame buffer size in the downlink direction, in units of 16
+    /// octets (type 7).
+    MD(u16),
+    
+    /// I frame buffer size in the uplink direction, in units of 16
+    /// octets (type 8).
+    MU(u16),
+    
+    /// Window size in the downlink direction (type 9).
+    KD(u8),
+    
+    /// Window size in the uplink direction (type 10).
+    KU(u8),
+    
+    /// Parameters of layer 3 (SNDCP), left undecoded (type 11).
+    Layer3Parameters(Vec<u8>),
+    
+    /// Reset of all the LLEs of the logical link, including their
+    /// overflow counters (type 12).
+    Reset,
+    
+    /// Parameter of an unknown type.
+    Unknown {
+        parameter_type: u8,
+        value: Vec<u8>
+    }
+}
+
+impl XidParameter {
+    /// Type of the parameter (5 bits).
+    pub fn parameter_type(&self) -> u8 {
+        match self {
+            XidParameter::Version(_) => 0,
+            XidParameter::IovUi(_) => 1,
+            XidParameter::IovI(_) => 2,
+            XidParameter::T200(_) => 3,
+            XidParameter::N200(_) => 4,
+            XidParameter::N201U(_) => 5,
+            XidParameter::N201I(_) => 6,
+            XidParameter::MD(_) => 7,
+            XidParameter::MU(_) => 8,
+            XidParameter::KD(_) => 9,
+            XidParameter::KU(_) => 10,
+            XidParameter::Layer3Parameters(_) => 11,
+            XidParameter::Reset => 12,
+            XidParameter::Unknown { parameter_type, .. } => parameter_type & 0x1f
+        }
+    }
+    
+    /// Decode a parameter from its type and value.
+    pub fn from_value(parameter_type: u8, value: &[u8]) -> Result<Self, LlcError> {
+        let integer = |length: usize| -> Result<u32, LlcError> {
+            if value.len() != length {
+                return Err(LlcError::InvalidXidParameter);
+            }
+            Ok(value.iter().fold(0, |integer, byte| (integer << 8) | *byte as u32))
+        };
+        Ok(match parameter_type {
+            0 => XidParameter::Version(integer(1)? as u8),
+            1 => XidParameter::IovUi(integer(4)?),
+            2 => XidParameter::IovI(integer(4)?),
+            3 => XidParameter::T200(integer(2)? as u16),
+            4 => XidParameter::N200(integer(1)? as u8),
+            5 => XidParameter::N201U(integer(2)? as u16),
+            6 => XidParameter::N201I(integer(2)? as u16),
+            7 => XidParameter::MD(integer(2)? as u16),
+            8 => XidParameter::MU(integer(2)? as u16),
+            9 => XidParameter::KD(integer(1)? as u8),
+            10 => XidParameter::KU(integer(1)? as u8),
+            11 => XidParameter::Layer3Parameters(value.to_vec()),
+            12 => {
+                integer(0)?;
+                XidParameter::Reset
+            },
+            parameter_type => XidParameter::Unknown { parameter_type, value: value.to_vec() }
+        })
+    }
+    
+    /// Encode the value of the parameter.
+    pub fn value(&self) -> Vec<u8> {
+        match self {
+            XidParameter::Version(value) | XidParameter::N200(value) |
+            XidParameter::KD(value) | XidParameter::KU(value) => vec![*value],
+            XidParameter::IovUi(value) | XidParameter::IovI(value) => value.to_be_bytes().to_vec(),
+            XidParameter::T200(value) | XidParameter::N201U(value) | XidParameter::N201I(value) |
+            XidParameter::MD(value) | XidParameter::MU(value) => value.to_be_bytes().to_vec(),
+            XidParameter::Layer3Parameters(value) | XidParameter::Unknown { value, .. } => value.clone(),
+            XidParameter::Reset => vec![]
+        }
+    }
+    
+    /// Parse the parameters making up an XID information field. Each
+    /// of them starts with a one octet header (XL bit, 5-bit type and
+    /// 2-bit length), the length being extended to 8 bits over the next
+    /// octet when the XL bit is set.
+    pub fn parse_list(data: &[u8]) -> Result<Vec<Self>, LlcError> {
+        let mut parameters = vec![];
+        let mut position = 0;
+        while position < data.len() {
+            let header = data[position];
+            let parameter_type = (header >> 2) & 0x1f;
+            let mut length = (header & 0x03) as usize;
+            position += 1;
+            if header & 0x80 != 0 {
+                let extension = *data.get(position).ok_or(LlcError::InvalidXidParameter)?;
+                length = (length << 6) | (extension >> 2) as usize;
+                position += 1;
+            }
+            let value = data.get(position..position + length).ok_or(LlcError::InvalidXidParameter)?;
+            parameters.push(Self::from_value(parameter_type, value)?);
+            position += length;
+        }
+        Ok(parameters)
+    }
+    
+    /// Encode parameters into an XID information field, extending the
+    /// length field only when required. Values longer than 255 octets
+    /// cannot be encoded, even with the extended length field.
+    pub fn serialize_list(parameters: &[Self]) -> Result<Vec<u8>, LlcError> {
+        let mut data = vec![];
+        for parameter in parameters {
+            let value = parameter.value();
+            let header = parameter.parameter_type() << 2;
+            if value.len() < 4 {
+                data.push(header | value.len() as u8);
+            } else if value.len() <= 0xff {
+                data.push(0x80 | header | (value.len() >> 6) as u8 & 0x03);
+                data.push(((value.len() & 0x3f) << 2) as u8);
+            } else {
+                return Err(LlcError::InvalidXidParameter);
+            }
+            data.extend_from_slice(&value);
+        }
+        Ok(data)
+    }
+}
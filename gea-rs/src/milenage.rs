@@ -0,0 +1,185 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : milenage.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Milenage authentication and key generation functions f1 to f5*, as
+//  specified by 3GPP TS 35.206, and the conversion of UMTS keys to the
+//  GSM/GPRS Kc (c3 function of 3GPP TS 33.102)
+//-----------------------------------------------------------------------------/
+
+
+use crate::aes::Aes128;
+use crate::key::{Kc, Kc128};
+use crate::sha256::hmac_sha256;
+
+/// Rotation amounts (in bits) and constants of f1 to f5.
+const ROTATIONS: [usize; 5] = [64, 0, 32, 64, 96];
+const CONSTANTS: [u8; 5] = [0, 1, 2, 4, 8];
+
+/// Outputs of f1 and f1*.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MilenageMac {
+    /// Network authentication code (f1).
+    pub mac_a: [u8; 8],
+    
+    /// Resynchronisation authentication code (f1*).
+    pub mac_s: [u8; 8]
+}
+
+/// Outputs of f2 to f5 and f5*.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MilenageKeys {
+    pub res: [u8; 8],
+    pub ck: [u8; 16],
+    pub ik: [u8; 16],
+    pub ak: [u8; 6],
+    
+    /// Anonymity key for resynchronisation (f5*).
+    pub ak_s: [u8; 6]
+}
+
+impl MilenageKeys {
+    /// Derive the GSM/GPRS Kc from CK and IK (c3 function).
+    pub fn kc(&self) -> Kc {
+        c3(&self.ck, &self.ik)
+    }
+    
+    /// Derive the GEA4 Kc128 from CK and IK.
+    pub fn kc128(&self) -> Kc128 {
+        kc128(&self.ck, &self.ik)
+    }
+    
+    /// Derive the GSM SRES from RES (c2 function, for a 64-bit RES).
+    pub fn sres(&self) -> [u8; 4] {
+        let mut sres = [0; 4];
+        for (position, byte) in sres.iter_mut().enumerate() {
+            *byte = self.res[position] ^ self.res[position + 4];
+        }
+        sres
+    }
+}
+
+/// Milenage instance for a subscriber key K and its OPc.
+pub struct Milenage {
+    cipher: Aes128,
+    opc: [u8; 16]
+}
+
+impl Milenage {
+    pub fn new(k: &[u8; 16], opc: &[u8; 16]) -> Self {
+        Self { cipher: Aes128::new(k), opc: *opc }
+    }
+    
+    /// Create an instance from the operator variant OP, computing OPc.
+    pub fn with_op(k: &[u8; 16], op: &[u8; 16]) -> Self {
+        Self::new(k, &compute_opc(k, op))
+    }
+    
+    pub fn opc(&self) -> [u8; 16] {
+        self.opc
+    }
+    
+    /// Compute f1 and f1*, from RAND, SQN and AMF.
+    pub fn f1(&self, rand: &[u8; 16], sqn: &[u8; 6], amf: &[u8; 2]) -> MilenageMac {
+        let temp = self.temp(rand);
+        let mut in1 = [0u8; 16];
+        for half in in1.chunks_mut(8) {
+            half[..6].copy_from_slice(sqn);
+            half[6..].copy_from_slice(amf);
+        }
+        let mut input = xor(&in1, &self.opc);
+        input = rotate(&input, ROTATIONS[0]);
+        input[15] ^= CONSTANTS[0];
+        let out1 = xor(&self.cipher.encrypt_block(&xor(&input, &temp)), &self.opc);
+        
+        let (mut mac_a, mut mac_s) = ([0; 8], [0; 8]);
+        mac_a.copy_from_slice(&out1[..8]);
+        mac_s.copy_from_slice(&out1[8..]);
+        MilenageMac { mac_a, mac_s }
+    }
+    
+    /// Compute f2 to f5 and f5*, from RAND.
+    pub fn f2_f5(&self, rand: &[u8; 16]) -> MilenageKeys {
+        let temp = self.temp(rand);
+        let output = |function: usize| -> [u8; 16] {
+            let mut input = rotate(&xor(&temp, &self.opc), ROTATIONS[function]);
+            input[15] ^= CONSTANTS[function];
+            xor(&self.cipher.encrypt_block(&input), &self.opc)
+        };
+        let (out2, out5) = (output(1), output(4));
+        
+        let (mut res, mut ak, mut ak_s) = ([0; 8], [0; 6], [0; 6]);
+        res.copy_from_slice(&out2[8..]);
+        ak.copy_from_slice(&out2[..6]);
+        ak_s.copy_from_slice(&out5[..6]);
+        MilenageKeys { res, ck: output(2), ik: output(3), ak, ak_s }
+    }
+    
+    fn temp(&self, rand: &[u8; 16]) -> [u8; 16] {
+        self.cipher.encrypt_block(&xor(rand, &self.opc))
+    }
+}
+
+/// Compute OPc from K and OP.
+pub fn compute_opc(k: &[u8; 16], op: &[u8; 16]) -> [u8; 16] {
+    xor(&Aes128::new(k).encrypt_block(op), op)
+}
+
+/// Conversion of CK and IK into Kc (c3 function of 3GPP TS 33.102):
+/// Kc = CK1 xor CK2 xor IK1 xor IK2, over the 64-bit halves of the keys.
+pub fn c3(ck: &[u8; 16], ik: &[u8; 16]) -> Kc {
+    let mut kc = [0; 8];
+    for (position, byte) in kc.iter_mut().enumerate() {
+        *byte = ck[position] ^ ck[position + 8] ^ ik[position] ^ ik[position + 8];
+    }
+    Kc::from_bytes(kc)
+}
+
+/// Derivation of Kc128 from CK and IK (3GPP TS 33.102, using the key
+/// derivation function of TS 33.220 with FC = 0x32 and no parameter):
+/// the 128 least significant bits of HMAC-SHA-256(CK || IK, FC).
+pub fn kc128(ck: &[u8; 16], ik: &[u8; 16]) -> Kc128 {
+    let mut key = [0; 32];
+    key[..16].copy_from_slice(ck);
+    key[16..].copy_from_slice(ik);
+    let output = hmac_sha256(&key, &[0x32]);
+    let mut kc128 = [0; 16];
+    kc128.copy_from_slice(&output[16..]);
+    Kc128::from_bytes(kc128)
+}
+
+fn xor(left: &[u8; 16], right: &[u8; 16]) -> [u8; 16] {
+    let mut output = [0; 16];
+    for (position, byte) in output.iter_mut().enumerate() {
+        *byte = left[position] ^ right[position];
+    }
+    output
+}
+
+/// Rotate a 128-bit value to the left, by a multiple of 8 bits.
+fn rotate(value: &[u8; 16], bits: usize) -> [u8; 16] {
+    let mut output = *value;
+    output.rotate_left(bits / 8);
+    output
+}
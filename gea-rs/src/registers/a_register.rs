@@ -37,6 +37,16 @@ use crate::f_lookup_table::F_LOOKUP_TABLE;
 pub struct ARegister(pub u64);
 
 impl ARegister {
+    /// Size of the register, in bits.
+    pub const SIZE: usize = 31;
+    
+    /// Bits xor'ed into the register when the rotated bit is set.
+    pub const TAPS: u64 = 0b11101110110001001101110001101;
+    
+    /// Positions of the register bits used as the x0 to x6 inputs
+    /// of the "f" function.
+    pub const F_INPUTS: [usize; 7] = [22, 0, 13, 21, 25, 2, 7];
+    
     pub fn initialize(vector_data: u128, vector_size: usize) -> Self {
         let mut a_register = Self(0);
         a_register.initial_clock(vector_data, vector_size);
@@ -54,10 +64,11 @@ impl LinearFeedbackShiftRegister for ARegister {
             (((self.0 & 1) ^ bit) << 30);
         
         if self.0 >> 30 == 1 {
-            self.0 ^= 0b11101110110001001101110001101;
+            self.0 ^= Self::TAPS;
         }
     }
     
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn f_function(&mut self) -> bool {
         F_LOOKUP_TABLE[(
             ((self.0 >> (22 - 0)) & 0b000_0001) |
@@ -37,6 +37,16 @@ use crate::f_lookup_table::F_LOOKUP_TABLE;
 pub struct BRegister(pub u64);
 
 impl BRegister {
+    /// Size of the register, in bits.
+    pub const SIZE: usize = 32;
+    
+    /// Bits xor'ed into the register when the rotated bit is set.
+    pub const TAPS: u64 = 0b1110001110000001111000001000101;
+    
+    /// Positions of the register bits used as the x0 to x6 inputs
+    /// of the "f" function.
+    pub const F_INPUTS: [usize; 7] = [12, 27, 0, 1, 29, 21, 5];
+    
     pub fn initialize(vector_data: u128, vector_size: usize) -> Self {
         let mut b_register = Self(0);
         b_register.initial_clock(vector_data, vector_size);
@@ -54,10 +64,11 @@ impl LinearFeedbackShiftRegister for BRegister {
             (((self.0 & 1) ^ bit) << 31);
         
         if self.0 >> 31 == 1 {
-            self.0 ^= 0b1110001110000001111000001000101;
+            self.0 ^= Self::TAPS;
         }
     }
     
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn f_function(&mut self) -> bool {
         F_LOOKUP_TABLE[(
             ((self.0 >> (12 - 0)) & 0b000_0001) |
@@ -37,6 +37,16 @@ use crate::f_lookup_table::F_LOOKUP_TABLE;
 pub struct CRegister(pub u64);
 
 impl CRegister {
+    /// Size of the register, in bits.
+    pub const SIZE: usize = 33;
+    
+    /// Bits xor'ed into the register when the rotated bit is set.
+    pub const TAPS: u64 = 0b1010000111001101111101000100100;
+    
+    /// Positions of the register bits used as the x0 to x6 inputs
+    /// of the "f" function.
+    pub const F_INPUTS: [usize; 7] = [10, 30, 32, 3, 19, 0, 4];
+    
     pub fn initialize(vector_data: u128, vector_size: usize) -> Self {
         let mut c_register = Self(0);
         c_register.initial_clock(vector_data, vector_size);
@@ -54,10 +64,11 @@ impl LinearFeedbackShiftRegister for CRegister {
             (((self.0 & 1) ^ bit) << 32);
         
         if self.0 >> 32 == 1 {
-            self.0 ^= 0b1010000111001101111101000100100;
+            self.0 ^= Self::TAPS;
         }
     }
     
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn f_function(&mut self) -> bool {
         F_LOOKUP_TABLE[(
             ((self.0 >> (10 - 0)) & 0b000_0001) |
@@ -39,6 +39,16 @@ use crate::f_lookup_table::F_LOOKUP_TABLE;
 pub struct DRegister(pub u64);
 
 impl DRegister {
+    /// Size of the register, in bits.
+    pub const SIZE: usize = 29;
+    
+    /// Bits xor'ed into the register when the rotated bit is set.
+    pub const TAPS: u64 = 0b1010010110011010101111111001;
+    
+    /// Positions of the register bits used as the x0 to x6 inputs
+    /// of the "f" function.
+    pub const F_INPUTS: [usize; 7] = [12, 23, 3, 0, 10, 27, 17];
+    
     pub fn initialize(vector_data: u128, vector_size: usize) -> Self {
         let mut d_register = Self(0);
         d_register.initial_clock(vector_data, vector_size);
@@ -56,10 +66,11 @@ impl LinearFeedbackShiftRegister for DRegister {
             (((self.0 & 1) ^ bit) << 28);
         
         if self.0 >> 28 == 1 {
-            self.0 ^= 0b1010010110011010101111111001;
+            self.0 ^= Self::TAPS;
         }
     }
     
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn f_function(&mut self) -> bool {
         F_LOOKUP_TABLE[(
             ((self.0 >> (12 - 0)) & 0b000_0001) |
@@ -29,7 +29,7 @@
 //-----------------------------------------------------------------------------/
 
 
-use crate::lfsr::LinearFeedbackShiftRegister;
+use crate::lfsr::{LinearFeedbackShiftRegister, recover_initialization_key};
 use crate::link_direction::LinkDirection;
 use crate::f_lookup_table::F_LOOKUP_TABLE;
 
@@ -41,9 +41,26 @@ use crate::f_lookup_table::F_LOOKUP_TABLE;
 pub struct SRegister(pub u128);
 
 impl SRegister {
+    /// Size of the register, in bits.
+    pub const SIZE: usize = 64;
+    
+    /// Positions of the register bits used as the x0 to x6 inputs
+    /// of the "f" function.
+    pub const F_INPUTS: [usize; 7] = [3, 12, 22, 38, 42, 55, 63];
+    
+    /// Number of clocks run over the register, without input data,
+    /// after the key has been loaded.
+    pub const MIXING_CLOCKS: usize = 128;
+    
     /// Initialize the S Register from a GEA-1 key, IV
     /// and direction bit.
     pub fn initialize(key: u64, iv: u32, direction: LinkDirection) -> Self {
+        Self::initialize_reduced(key, iv, direction, Self::MIXING_CLOCKS)
+    }
+    
+    /// Initialize the S Register with a reduced (or increased) number
+    /// of mixing clocks, for cryptanalysis purposes.
+    pub fn initialize_reduced(key: u64, iv: u32, direction: LinkDirection, mixing_clocks: usize) -> Self {
         let mut s_register = Self(0);
         s_register.initial_clock(iv as u128, 32);
         s_register.initial_clock(match direction {
@@ -51,9 +68,16 @@ impl SRegister {
             LinkDirection::Downlink => 1
         }, 1);
         s_register.initial_clock(key as u128, 64);
-        s_register.initial_clock(0, 128);
+        s_register.initial_clock(0, mixing_clocks);
         s_register
     }
+    
+    /// Recover the key from an initialized S Register, given the IV
+    /// and direction bit, through running the initialization backwards
+    /// (as done in the cryptanalysis paper).
+    pub fn recover_key(&self, iv: u32, direction: LinkDirection) -> u64 {
+        recover_initialization_key(self.0, Self::SIZE, Self::F_INPUTS, Self::MIXING_CLOCKS, iv, direction)
+    }
 }
 
 impl LinearFeedbackShiftRegister for SRegister {
@@ -63,6 +87,7 @@ impl LinearFeedbackShiftRegister for SRegister {
             (((self.0 & 1) ^ (self.f_function() as u128) ^ bit) << 63);
     }
     
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn f_function(&mut self) -> bool {
         F_LOOKUP_TABLE[(
             ((self.0 >> (3 - 0)) & 0b000_0001) |
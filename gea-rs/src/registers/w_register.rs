@@ -29,7 +29,7 @@
 //-----------------------------------------------------------------------------/
 
 
-use crate::lfsr::LinearFeedbackShiftRegister;
+use crate::lfsr::{LinearFeedbackShiftRegister, recover_initialization_key};
 use crate::link_direction::LinkDirection;
 use crate::f_lookup_table::F_LOOKUP_TABLE;
 
@@ -41,9 +41,26 @@ use crate::f_lookup_table::F_LOOKUP_TABLE;
 pub struct WRegister(pub u128);
 
 impl WRegister {
+    /// Size of the register, in bits.
+    pub const SIZE: usize = 97;
+    
+    /// Positions of the register bits used as the x0 to x6 inputs
+    /// of the "f" function.
+    pub const F_INPUTS: [usize; 7] = [4, 18, 33, 57, 63, 83, 96];
+    
+    /// Number of clocks run over the register, without input data,
+    /// after the key has been loaded.
+    pub const MIXING_CLOCKS: usize = 194;
+    
     /// Initialize the W Register from a GEA-1 key, IV
     /// and direction bit.
     pub fn initialize(key: u64, iv: u32, direction: LinkDirection) -> Self {
+        Self::initialize_reduced(key, iv, direction, Self::MIXING_CLOCKS)
+    }
+    
+    /// Initialize the W Register with a reduced (or increased) number
+    /// of mixing clocks, for cryptanalysis purposes.
+    pub fn initialize_reduced(key: u64, iv: u32, direction: LinkDirection, mixing_clocks: usize) -> Self {
         let mut w_register = Self(0);
         w_register.initial_clock(iv as u128, 32);
         w_register.initial_clock(match direction {
@@ -51,9 +68,16 @@ impl WRegister {
             LinkDirection::Downlink => 1
         }, 1);
         w_register.initial_clock(key as u128, 64);
-        w_register.initial_clock(0, 194);
+        w_register.initial_clock(0, mixing_clocks);
         w_register
     }
+    
+    /// Recover the key from an initialized W Register, given the IV
+    /// and direction bit, through running the initialization backwards
+    /// (as done in the cryptanalysis paper).
+    pub fn recover_key(&self, iv: u32, direction: LinkDirection) -> u64 {
+        recover_initialization_key(self.0, Self::SIZE, Self::F_INPUTS, Self::MIXING_CLOCKS, iv, direction)
+    }
 }
 
 impl LinearFeedbackShiftRegister for WRegister {
@@ -63,6 +87,7 @@ impl LinearFeedbackShiftRegister for WRegister {
             (((self.0 & 1) ^ (self.f_function() as u128) ^ bit) << 96);
     }
     
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn f_function(&mut self) -> bool {
         F_LOOKUP_TABLE[(
             ((self.0 >> (4 - 0)) & 0b000_0001) |
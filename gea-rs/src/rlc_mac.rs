@@ -0,0 +1,627 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : rlc_mac.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  RLC/MAC data blocks, as specified by 3GPP TS 44.060: decoding of
+//  GPRS and EGPRS data block headers, and reassembly of the LLC PDUs
+//  carried by the data blocks of each TBF
+//-----------------------------------------------------------------------------/
+
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::link_direction::LinkDirection;
+
+/// Number of BSNs within which GPRS (SNS = 128, WS = 64) and EGPRS
+/// (SNS = 2048) blocks may be received out of order.
+pub const GPRS_SEQUENCE_NUMBER_SPACE: u16 = 128;
+pub const GPRS_WINDOW_SIZE: u16 = 64;
+pub const EGPRS_SEQUENCE_NUMBER_SPACE: u16 = 2048;
+
+/// Error encountered when decoding an RLC/MAC block.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RlcMacError {
+    /// The block is shorter than its header, length indicators, or
+    /// than announced by them.
+    TooShort,
+    
+    /// The block is a control block rather than a data block.
+    NotDataBlock,
+    
+    /// The block length does not match any coding scheme.
+    UnknownCodingScheme,
+    
+    /// The EGPRS block carries half of a data unit retransmitted over
+    /// two blocks, which is not supported.
+    SplitBlock
+}
+
+impl fmt::Display for RlcMacError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            RlcMacError::TooShort => "RLC/MAC block too short",
+            RlcMacError::NotDataBlock => "RLC/MAC block is not a data block",
+            RlcMacError::UnknownCodingScheme => "unknown RLC/MAC coding scheme",
+            RlcMacError::SplitBlock => "split EGPRS RLC/MAC block"
+        })
+    }
+}
+
+impl std::error::Error for RlcMacError {}
+
+/// Coding scheme of GPRS data blocks, which determines the length of
+/// their RLC data unit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GprsCodingScheme {
+    CS1,
+    CS2,
+    CS3,
+    CS4
+}
+
+impl GprsCodingScheme {
+    /// Coding scheme of a block, from its length in octets (including
+    /// the final partial octet of CS-2 to CS-4 blocks, if present).
+    pub fn from_block_length(length: usize) -> Option<Self> {
+        match length {
+            23 => Some(GprsCodingScheme::CS1),
+            33 | 34 => Some(GprsCodingScheme::CS2),
+            39 | 40 => Some(GprsCodingScheme::CS3),
+            53 | 54 => Some(GprsCodingScheme::CS4),
+            _ => None
+        }
+    }
+    
+    /// Length of the RLC data unit (following the three octets of MAC
+    /// and RLC header).
+    pub fn data_unit_length(self) -> usize {
+        match self {
+            GprsCodingScheme::CS1 => 20,
+            GprsCodingScheme::CS2 => 30,
+            GprsCodingScheme::CS3 => 36,
+            GprsCodingScheme::CS4 => 50
+        }
+    }
+}
+
+/// Modulation and coding scheme of EGPRS data blocks, which determines
+/// their header type and the length of their RLC data units.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EgprsCodingScheme {
+    MCS1,
+    MCS2,
+    MCS3,
+    MCS4,
+    MCS5,
+    MCS6,
+    MCS7,
+    MCS8,
+    MCS9
+}
+
+impl EgprsCodingScheme {
+    /// Coding scheme of a block sent in the given direction, from its
+    /// length in octets (including its final partial octet).
+    pub fn from_block_length(direction: LinkDirection, length: usize) -> Option<Self> {
+        match (direction, length) {
+            (_, 27) => Some(EgprsCodingScheme::MCS1),
+            (_, 33) => Some(EgprsCodingScheme::MCS2),
+            (_, 42) => Some(EgprsCodingScheme::MCS3),
+            (_, 49) => Some(EgprsCodingScheme::MCS4),
+            (LinkDirection::Downlink, 60) | (LinkDirection::Uplink, 61) => Some(EgprsCodingScheme::MCS5),
+            (LinkDirection::Downlink, 78) | (LinkDirection::Uplink, 79) => Some(EgprsCodingScheme::MCS6),
+            (LinkDirection::Downlink, 118) | (LinkDirection::Uplink, 119) => Some(EgprsCodingScheme::MCS7),
+            (LinkDirection::Downlink, 142) | (LinkDirection::Uplink, 143) => Some(EgprsCodingScheme::MCS8),
+            (LinkDirection::Downlink, 154) | (LinkDirection::Uplink, 155) => Some(EgprsCodingScheme::MCS9),
+            _ => None
+        }
+    }
+    
+    /// Type of the RLC/MAC header of the blocks.
+    pub fn header_type(self) -> u8 {
+        match self {
+            EgprsCodingScheme::MCS1 | EgprsCodingScheme::MCS2 |
+                EgprsCodingScheme::MCS3 | EgprsCodingScheme::MCS4 => 3,
+            EgprsCodingScheme::MCS5 | EgprsCodingScheme::MCS6 => 2,
+            EgprsCodingScheme::MCS7 | EgprsCodingScheme::MCS8 | EgprsCodingScheme::MCS9 => 1
+        }
+    }
+    
+    /// Length of each RLC data unit, in octets.
+    pub fn data_unit_length(self) -> usize {
+        match self {
+            EgprsCodingScheme::MCS1 => 22,
+            EgprsCodingScheme::MCS2 => 28,
+            EgprsCodingScheme::MCS3 => 37,
+            EgprsCodingScheme::MCS4 => 44,
+            EgprsCodingScheme::MCS5 | EgprsCodingScheme::MCS7 => 56,
+            EgprsCodingScheme::MCS6 | EgprsCodingScheme::MCS9 => 74,
+            EgprsCodingScheme::MCS8 => 68
+        }
+    }
+    
+    /// Number of RLC data units carried by each block.
+    pub fn num_data_units(self) -> usize {
+        if self.header_type() == 1 { 2 } else { 1 }
+    }
+}
+
+/// Length indicator octet of a data block: in GPRS, a 6-bit length
+/// followed by the M and E bits; in EGPRS, a 7-bit length followed by
+/// the E bit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LengthIndicator {
+    pub length: u8,
+    
+    /// M bit: another LLC PDU follows the one delimited (GPRS only).
+    pub more: bool
+}
+
+/// Length indicator value marking, in EGPRS, that the rest of the data
+/// unit is filling octets.
+const EGPRS_FILLING_INDICATOR: u8 = 127;
+
+/// Data block of a TBF.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RlcDataBlock {
+    pub direction: LinkDirection,
+    pub egprs: bool,
+    
+    /// Temporary Flow Identity of the TBF (5 bits).
+    pub tfi: u8,
+    
+    /// Block Sequence Number (7 bits in GPRS, 11 bits in EGPRS).
+    pub bsn: u16,
+    
+    /// Last block of the TBF: FBI bit in the downlink direction,
+    /// countdown value of zero in the uplink direction.
+    pub final_block: bool,
+    
+    pub length_indicators: Vec<LengthIndicator>,
+    
+    /// TLLI of the MS, sent in uplink blocks during contention
+    /// resolution (TI bit).
+    pub tlli: Option<u32>,
+    
+    /// Packet Flow Identifier (PI bit, uplink only).
+    pub pfi: Option<u8>,
+    
+    /// Data following the header, length indicators and TLLI/PFI.
+    pub data: Vec<u8>
+}
+
+/// Segment of an LLC PDU carried by a data block.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LlcSegment {
+    pub data: Vec<u8>,
+    
+    /// The segment is the last one of its LLC PDU.
+    pub last: bool
+}
+
+impl RlcDataBlock {
+    /// Parse a GPRS data block (coding schemes CS-1 to CS-4) sent in the
+    /// given direction, including its MAC header.
+    pub fn parse_gprs(direction: LinkDirection, block: &[u8]) -> Result<Self, RlcMacError> {
+        if block.len() < 3 {
+            return Err(RlcMacError::TooShort);
+        }
+        if block[0] & 0xc0 != 0 {
+            return Err(RlcMacError::NotDataBlock);
+        }
+        let coding_scheme = GprsCodingScheme::from_block_length(block.len()).ok_or(RlcMacError::UnknownCodingScheme)?;
+        let data_unit = &block[3..3 + coding_scheme.data_unit_length()];
+        let (final_block, tlli_indicator, pfi_indicator) = match direction {
+            LinkDirection::Downlink => (block[1] & 0x01 != 0, false, false),
+            LinkDirection::Uplink => ((block[0] >> 2) & 0x0f == 0, block[1] & 0x01 != 0, block[1] & 0x40 != 0)
+        };
+        
+        let mut data_block = Self {
+            direction,
+            egprs: false,
+            tfi: (block[1] >> 1) & 0x1f,
+            bsn: (block[2] >> 1) as u16,
+            final_block,
+            length_indicators: vec![],
+            tlli: None,
+            pfi: None,
+            data: vec![]
+        };
+        data_block.parse_data_unit(block[2] & 0x01 != 0, tlli_indicator, pfi_indicator, data_unit)?;
+        Ok(data_block)
+    }
+    
+    /// Parse the RLC data unit of an EGPRS data block, once its header
+    /// has been decoded and the data unit extracted from the coded block
+    /// (at an offset depending on the MCS) into an octet-aligned buffer.
+    /// "extension" is the E bit preceding the data unit, and
+    /// "final_or_tlli_indicator" the FBI bit in the downlink direction,
+    /// or the TI bit in the uplink direction. MCS-7 to MCS-9 blocks carry
+    /// two data units, the second one having index 1.
+    pub fn parse_egprs(header: &EgprsHeader, block_index: usize, extension: bool, final_or_tlli_indicator: bool,
+            data_unit: &[u8]) -> Result<Self, RlcMacError> {
+        let bsn = match block_index {
+            0 => header.bsn,
+            _ => (header.bsn + header.bsn2_offset.unwrap_or(0)) % EGPRS_SEQUENCE_NUMBER_SPACE
+        };
+        let (final_block, tlli_indicator) = match header.countdown_value {
+            Some(countdown_value) => (countdown_value == 0, final_or_tlli_indicator),
+            None => (final_or_tlli_indicator, false)
+        };
+        let mut block = Self {
+            direction: header.direction,
+            egprs: true,
+            tfi: header.tfi,
+            bsn,
+            final_block,
+            length_indicators: vec![],
+            tlli: None,
+            pfi: None,
+            data: vec![]
+        };
+        block.parse_data_unit(extension, tlli_indicator, header.pfi_indicator, data_unit)?;
+        Ok(block)
+    }
+    
+    /// Parse an EGPRS data block (coding schemes MCS-1 to MCS-9) sent in
+    /// the given direction, as decoded from the radio blocks (RLC/MAC
+    /// header then data units, least significant bit first). One data
+    /// block is returned for each of its data units.
+    pub fn parse_egprs_block(direction: LinkDirection, block: &[u8]) -> Result<Vec<Self>, RlcMacError> {
+        let coding_scheme = EgprsCodingScheme::from_block_length(direction, block.len())
+            .ok_or(RlcMacError::UnknownCodingScheme)?;
+        let header = EgprsHeader::parse(direction, coding_scheme.header_type(), block)?;
+        if header.split_block != 0 {
+            return Err(RlcMacError::SplitBlock);
+        }
+        
+        // Each data unit is preceded by its E bit, then the FBI (downlink)
+        // or TI (uplink) bit. Padding octets, if any, follow the header.
+        let padding_length = if header.with_padding() { 6 } else { 0 };
+        let data_unit_length = coding_scheme.data_unit_length() - padding_length;
+        let mut position = header.length_bits() + 8 * padding_length;
+        let mut blocks = vec![];
+        for block_index in 0..coding_scheme.num_data_units() {
+            let data_unit: Vec<u8> = (0..data_unit_length)
+                .map(|octet_index| read_bits(block, position + 2 + 8 * octet_index, 8) as u8)
+                .collect();
+            blocks.push(Self::parse_egprs(&header, block_index, read_bits(block, position, 1) != 0,
+                read_bits(block, position + 1, 1) != 0, &data_unit)?);
+            position += 2 + 8 * data_unit_length;
+        }
+        Ok(blocks)
+    }
+    
+    /// Parse the length indicators, TLLI and PFI of a data unit, and
+    /// keep the data following them.
+    fn parse_data_unit(&mut self, mut extension: bool, tlli_indicator: bool, pfi_indicator: bool,
+            data_unit: &[u8]) -> Result<(), RlcMacError> {
+        let mut position = 0;
+        while !extension {
+            let octet = *data_unit.get(position).ok_or(RlcMacError::TooShort)?;
+            self.length_indicators.push(match self.egprs {
+                false => LengthIndicator { length: octet >> 2, more: octet & 0x02 != 0 },
+                true => LengthIndicator { length: octet >> 1, more: false }
+            });
+            extension = octet & 0x01 != 0;
+            position += 1;
+        }
+        if tlli_indicator {
+            let tlli = data_unit.get(position..position + 4).ok_or(RlcMacError::TooShort)?;
+            self.tlli = Some(u32::from_be_bytes([tlli[0], tlli[1], tlli[2], tlli[3]]));
+            position += 4;
+        }
+        if pfi_indicator {
+            self.pfi = Some(*data_unit.get(position).ok_or(RlcMacError::TooShort)? >> 1);
+            position += 1;
+        }
+        self.data = data_unit.get(position..).ok_or(RlcMacError::TooShort)?.to_vec();
+        Ok(())
+    }
+    
+    /// Split the data of the block into LLC PDU segments, using its
+    /// length indicators. Data not delimited by a length indicator
+    /// continues in the next block, unless this block is the final one.
+    pub fn segments(&self) -> Result<Vec<LlcSegment>, RlcMacError> {
+        let mut segments = vec![];
+        let mut position = 0;
+        let mut continued = true;
+        for (indicator_index, indicator) in self.length_indicators.iter().enumerate() {
+            let is_last_indicator = indicator_index + 1 == self.length_indicators.len();
+            if self.egprs && indicator.length == EGPRS_FILLING_INDICATOR {
+                continued = false;
+                break;
+            }
+            let length = match (self.egprs, indicator.length) {
+                // GPRS: the LLC PDU fills the rest of the block exactly
+                (false, 0) => self.data.len() - position,
+                // EGPRS: the previous LLC PDU ended with the previous block
+                (true, 0) => 0,
+                (_, length) => length as usize
+            };
+            let segment = self.data.get(position..position + length).ok_or(RlcMacError::TooShort)?;
+            segments.push(LlcSegment { data: segment.to_vec(), last: true });
+            position += length;
+            if is_last_indicator && !self.egprs {
+                continued = indicator.more;
+            }
+        }
+        if continued && position < self.data.len() {
+            segments.push(LlcSegment { data: self.data[position..].to_vec(), last: self.final_block });
+        }
+        Ok(segments)
+    }
+}
+
+/// RLC/MAC header of an EGPRS data block. The header type depends on the
+/// MCS: type 1 for MCS-7 to MCS-9 (two data units), type 2 for MCS-5 and
+/// MCS-6, type 3 for MCS-1 to MCS-4.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EgprsHeader {
+    pub direction: LinkDirection,
+    pub header_type: u8,
+    pub tfi: u8,
+    
+    /// BSN of the first data unit (11 bits).
+    pub bsn: u16,
+    
+    /// Offset of the BSN of the second data unit, for header type 1.
+    pub bsn2_offset: Option<u16>,
+    
+    /// Coding and Puncturing Scheme indicator.
+    pub cps: u8,
+    
+    /// Split Block indicator, for header type 3 (zero when the data unit
+    /// is not split over two blocks).
+    pub split_block: u8,
+    
+    /// Countdown value, for uplink headers.
+    pub countdown_value: Option<u8>,
+    
+    /// PI bit, for uplink headers.
+    pub pfi_indicator: bool
+}
+
+impl EgprsHeader {
+    /// Parse the (octet-aligned, least significant bit first) header of
+    /// an EGPRS data block of the given header type. Fields spanning two
+    /// octets have their least significant bits in the first one.
+    pub fn parse(direction: LinkDirection, header_type: u8, header: &[u8]) -> Result<Self, RlcMacError> {
+        let header_length = match (direction, header_type) {
+            (LinkDirection::Downlink, 1) => 5,
+            (LinkDirection::Downlink, 2) | (LinkDirection::Downlink, 3) => 4,
+            (LinkDirection::Uplink, 1) => 6,
+            (LinkDirection::Uplink, 2) => 5,
+            (LinkDirection::Uplink, 3) => 4,
+            _ => return Err(RlcMacError::UnknownCodingScheme)
+        };
+        if header.len() < header_length {
+            return Err(RlcMacError::TooShort);
+        }
+        
+        let field = |position: usize, num_bits: usize| read_bits(header, position, num_bits);
+        let mut parsed = Self {
+            direction,
+            header_type,
+            tfi: 0,
+            bsn: 0,
+            bsn2_offset: None,
+            cps: 0,
+            split_block: 0,
+            countdown_value: None,
+            pfi_indicator: false
+        };
+        match direction {
+            LinkDirection::Downlink => {
+                parsed.tfi = field(7, 5) as u8;
+                parsed.bsn = field(14, 11);
+                match header_type {
+                    1 => {
+                        parsed.bsn2_offset = Some(field(25, 10));
+                        parsed.cps = field(35, 5) as u8;
+                    },
+                    2 => parsed.cps = field(25, 3) as u8,
+                    _ => {
+                        parsed.cps = field(25, 4) as u8;
+                        parsed.split_block = field(29, 2) as u8;
+                    }
+                }
+            },
+            LinkDirection::Uplink => {
+                parsed.countdown_value = Some(field(2, 4) as u8);
+                parsed.tfi = field(6, 5) as u8;
+                parsed.bsn = field(11, 11);
+                match header_type {
+                    1 => {
+                        parsed.bsn2_offset = Some(field(22, 10));
+                        parsed.cps = field(32, 5) as u8;
+                        parsed.pfi_indicator = field(38, 1) != 0;
+                    },
+                    2 => {
+                        parsed.cps = field(22, 3) as u8;
+                        parsed.pfi_indicator = field(26, 1) != 0;
+                    },
+                    _ => {
+                        parsed.cps = field(22, 4) as u8;
+                        parsed.split_block = field(26, 2) as u8;
+                        parsed.pfi_indicator = field(29, 1) != 0;
+                    }
+                }
+            }
+        }
+        Ok(parsed)
+    }
+    
+    /// Length of the header, in bits.
+    pub fn length_bits(&self) -> usize {
+        match (self.direction, self.header_type) {
+            (LinkDirection::Downlink, 1) => 40,
+            (LinkDirection::Downlink, 2) => 28,
+            (LinkDirection::Uplink, 1) => 46,
+            (LinkDirection::Uplink, 2) => 37,
+            _ => 31
+        }
+    }
+    
+    /// Whether the data unit is preceded by six padding octets, as done
+    /// for MCS-3 and MCS-6 blocks retransmitting MCS-8 data.
+    pub fn with_padding(&self) -> bool {
+        match self.header_type {
+            2 => (2..=3).contains(&self.cps),
+            3 => (6..=8).contains(&self.cps),
+            _ => false
+        }
+    }
+}
+
+/// Read a field of bits, least significant bit first, from an
+/// octet-aligned buffer (whose first bit is the least significant bit of
+/// its first octet). Missing bits are read as zero.
+fn read_bits(data: &[u8], position: usize, num_bits: usize) -> u16 {
+    (0..num_bits).fold(0, |value, bit_pos| {
+        let bit = data.get((position + bit_pos) / 8).map_or(0, |octet| (octet >> ((position + bit_pos) % 8)) & 1);
+        value | ((bit as u16) << bit_pos)
+    })
+}
+
+/// LLC PDU reassembled from the data blocks of a TBF.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReassembledLlcPdu {
+    pub direction: LinkDirection,
+    pub tfi: u8,
+    
+    /// TLLI of the MS, if it was sent in an uplink block of the TBF.
+    pub tlli: Option<u32>,
+    
+    pub data: Vec<u8>
+}
+
+/// Reassembly state of a TBF.
+struct TbfState {
+    sequence_number_space: u16,
+    next_bsn: u16,
+    pending: BTreeMap<u16, RlcDataBlock>,
+    current_pdu: Vec<u8>,
+    
+    /// Whether the current PDU has been received from its start.
+    synchronized: bool,
+    
+    tlli: Option<u32>
+}
+
+/// Reassembly of the LLC PDUs carried by data blocks, for each TBF
+/// (identified by its direction and TFI). Blocks are delivered in BSN
+/// order, those received ahead of missing ones being held until these
+/// are received, or until "flush" is called. Reassembly of a TBF only
+/// starts with its first LLC PDU boundary, unless its block of BSN 0
+/// is received first.
+#[derive(Default)]
+pub struct LlcReassembler {
+    tbfs: HashMap<(LinkDirection, u8), TbfState>
+}
+
+impl LlcReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Process a data block, returning the LLC PDUs it completes.
+    pub fn push_block(&mut self, block: RlcDataBlock) -> Result<Vec<ReassembledLlcPdu>, RlcMacError> {
+        let key = (block.direction, block.tfi);
+        let sequence_number_space = if block.egprs { EGPRS_SEQUENCE_NUMBER_SPACE } else { GPRS_SEQUENCE_NUMBER_SPACE };
+        let state = self.tbfs.entry(key).or_insert_with(|| TbfState {
+            sequence_number_space,
+            next_bsn: block.bsn,
+            pending: BTreeMap::new(),
+            current_pdu: vec![],
+            synchronized: block.bsn == 0,
+            tlli: None
+        });
+        if block.tlli.is_some() {
+            state.tlli = block.tlli;
+        }
+        
+        // Blocks behind the next expected one are retransmissions
+        let distance = (block.bsn + sequence_number_space - state.next_bsn) % sequence_number_space;
+        if distance >= sequence_number_space / 2 {
+            return Ok(vec![]);
+        }
+        state.pending.insert(block.bsn, block);
+        
+        let mut pdus = vec![];
+        let mut finished = false;
+        while let Some(block) = state.pending.remove(&state.next_bsn) {
+            state.next_bsn = (state.next_bsn + 1) % state.sequence_number_space;
+            finished = block.final_block;
+            state.deliver(&block, &mut pdus)?;
+        }
+        if finished {
+            self.tbfs.remove(&key);
+        }
+        Ok(pdus)
+    }
+    
+    /// Deliver the blocks held for a TBF despite missing blocks, dropping
+    /// the LLC PDUs affected by the missing blocks.
+    pub fn flush(&mut self, direction: LinkDirection, tfi: u8) -> Result<Vec<ReassembledLlcPdu>, RlcMacError> {
+        let mut pdus = vec![];
+        if let Some(mut state) = self.tbfs.remove(&(direction, tfi)) {
+            let mut pending: Vec<RlcDataBlock> = std::mem::take(&mut state.pending).into_values().collect();
+            let (next_bsn, sequence_number_space) = (state.next_bsn, state.sequence_number_space);
+            pending.sort_by_key(|block| (block.bsn + sequence_number_space - next_bsn) % sequence_number_space);
+            let mut expected_bsn = state.next_bsn;
+            for block in pending {
+                if block.bsn != expected_bsn {
+                    state.current_pdu.clear();
+                    state.synchronized = false;
+                }
+                expected_bsn = (block.bsn + 1) % state.sequence_number_space;
+                state.deliver(&block, &mut pdus)?;
+            }
+        }
+        Ok(pdus)
+    }
+}
+
+impl TbfState {
+    fn deliver(&mut self, block: &RlcDataBlock, pdus: &mut Vec<ReassembledLlcPdu>) -> Result<(), RlcMacError> {
+        for segment in block.segments()? {
+            self.current_pdu.extend_from_slice(&segment.data);
+            if segment.last {
+                if self.synchronized && !self.current_pdu.is_empty() {
+                    pdus.push(ReassembledLlcPdu {
+                        direction: block.direction,
+                        tfi: block.tfi,
+                        tlli: self.tlli,
+                        data: std::mem::take(&mut self.current_pdu)
+                    });
+                }
+                self.current_pdu.clear();
+                self.synchronized = true;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : sha256.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  SHA-256 and HMAC-SHA-256, used by the 3GPP key derivation function
+//-----------------------------------------------------------------------------/
+
+
+/// First 32 bits of the fractional parts of the square roots (initial
+/// hash values) or cube roots (round constants) of the first primes.
+const fn root_constants<const N: usize>(cube: bool) -> [u32; N] {
+    let mut constants = [0u32; N];
+    let mut prime: u64 = 2;
+    let mut index = 0;
+    while index < N {
+        let mut divisor = 2;
+        let mut is_prime = true;
+        while divisor * divisor <= prime {
+            if prime % divisor == 0 {
+                is_prime = false;
+            }
+            divisor += 1;
+        }
+        if is_prime {
+            // Integer root of prime * 2^64 (square) or prime * 2^96
+            // (cube), by bisection: its low 32 bits are the fraction
+            let (mut low, mut high): (u128, u128) = (0, 1 << 42);
+            while high - low > 1 {
+                let middle = (low + high) / 2;
+                let power = if cube { middle * middle * middle } else { middle * middle };
+                let target = if cube { (prime as u128) << 96 } else { (prime as u128) << 64 };
+                if power <= target {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            constants[index] = low as u32;
+            index += 1;
+        }
+        prime += 1;
+    }
+    constants
+}
+
+const INITIAL_HASH: [u32; 8] = root_constants(false);
+const ROUND_CONSTANTS: [u32; 64] = root_constants(true);
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state = INITIAL_HASH;
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    
+    for block in message.chunks(64) {
+        let mut schedule = [0u32; 64];
+        for (position, word) in block.chunks(4).enumerate() {
+            schedule[position] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for position in 16..64 {
+            let (previous, previous_15) = (schedule[position - 2], schedule[position - 15]);
+            let sigma0 = previous_15.rotate_right(7) ^ previous_15.rotate_right(18) ^ (previous_15 >> 3);
+            let sigma1 = previous.rotate_right(17) ^ previous.rotate_right(19) ^ (previous >> 10);
+            schedule[position] = schedule[position - 16].wrapping_add(sigma0)
+                .wrapping_add(schedule[position - 7]).wrapping_add(sigma1);
+        }
+        
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for (round_constant, word) in ROUND_CONSTANTS.iter().zip(schedule.iter()) {
+            let sum1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h.wrapping_add(sum1).wrapping_add(choice).wrapping_add(*round_constant).wrapping_add(*word);
+            let sum0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = sum0.wrapping_add(majority);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+    
+    let mut digest = [0u8; 32];
+    for (position, word) in state.iter().enumerate() {
+        digest[4 * position..4 * position + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block_key = [0u8; 64];
+    if key.len() > 64 {
+        block_key[..32].copy_from_slice(&sha256(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+    let mut inner: Vec<u8> = block_key.iter().map(|byte| byte ^ 0x36).collect();
+    inner.extend_from_slice(data);
+    let mut outer: Vec<u8> = block_key.iter().map(|byte| byte ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
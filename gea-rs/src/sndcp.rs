@@ -0,0 +1,269 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : sndcp.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  SNDCP, as specified by 3GPP TS 44.065: parsing of the SN-DATA and
+//  SN-UNITDATA PDUs carried by deciphered LLC frames, and reassembly
+//  of their segments into N-PDUs
+//-----------------------------------------------------------------------------/
+
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Error encountered when parsing or reassembling SNDCP PDUs.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SndcpError {
+    /// The PDU is shorter than its header.
+    TooShort,
+    
+    /// A segment of an acknowledged N-PDU was received without its
+    /// first segment.
+    MissingFirstSegment
+}
+
+impl fmt::Display for SndcpError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            SndcpError::TooShort => "SNDCP PDU too short",
+            SndcpError::MissingFirstSegment => "SNDCP segment received without first segment"
+        })
+    }
+}
+
+impl std::error::Error for SndcpError {}
+
+/// Type of SNDCP PDU (T bit).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SndcpPduType {
+    /// SN-DATA, sent in acknowledged mode (in LLC I frames).
+    Data,
+    
+    /// SN-UNITDATA, sent in unacknowledged mode (in LLC UI frames).
+    Unitdata
+}
+
+/// SNDCP PDU, carrying a segment of an N-PDU.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SndcpPdu {
+    pub pdu_type: SndcpPduType,
+    
+    /// Network layer Service Access Point Identifier (4 bits).
+    pub nsapi: u8,
+    
+    /// F bit: the PDU carries the first segment of the N-PDU, and its
+    /// compression fields.
+    pub first_segment: bool,
+    
+    /// M bit: more segments of the N-PDU follow.
+    pub more_segments: bool,
+    
+    /// Data compression coding (4 bits, only present in first segments).
+    pub dcomp: u8,
+    
+    /// Protocol control information compression coding (4 bits, only
+    /// present in first segments).
+    pub pcomp: u8,
+    
+    /// Segment number (4 bits, SN-UNITDATA only).
+    pub segment_number: u8,
+    
+    /// N-PDU number: 12 bits in SN-UNITDATA PDUs, 8 bits in SN-DATA
+    /// PDUs where it is only present in first segments.
+    pub npdu_number: u16,
+    
+    pub data: Vec<u8>
+}
+
+impl SndcpPdu {
+    /// Parse a PDU from the information field of an LLC frame.
+    pub fn parse(data: &[u8]) -> Result<Self, SndcpError> {
+        let header = *data.first().ok_or(SndcpError::TooShort)?;
+        let first_segment = header & 0x40 != 0;
+        let pdu_type = if header & 0x20 != 0 { SndcpPduType::Unitdata } else { SndcpPduType::Data };
+        let mut position = 1;
+        
+        let (mut dcomp, mut pcomp) = (0, 0);
+        if first_segment {
+            let compression = *data.get(position).ok_or(SndcpError::TooShort)?;
+            dcomp = compression >> 4;
+            pcomp = compression & 0x0f;
+            position += 1;
+        }
+        
+        let (mut segment_number, mut npdu_number) = (0, 0);
+        match pdu_type {
+            SndcpPduType::Unitdata => {
+                let numbers = data.get(position..position + 2).ok_or(SndcpError::TooShort)?;
+                segment_number = numbers[0] >> 4;
+                npdu_number = (((numbers[0] & 0x0f) as u16) << 8) | numbers[1] as u16;
+                position += 2;
+            },
+            SndcpPduType::Data if first_segment => {
+                npdu_number = *data.get(position).ok_or(SndcpError::TooShort)? as u16;
+                position += 1;
+            },
+            SndcpPduType::Data => {}
+        }
+        
+        Ok(Self {
+            pdu_type,
+            nsapi: header & 0x0f,
+            first_segment,
+            more_segments: header & 0x10 != 0,
+            dcomp,
+            pcomp,
+            segment_number,
+            npdu_number,
+            data: data[position..].to_vec()
+        })
+    }
+    
+    /// Serialize the PDU into the information field of an LLC frame.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = vec![((self.first_segment as u8) << 6) |
+            (((self.pdu_type == SndcpPduType::Unitdata) as u8) << 5) |
+            ((self.more_segments as u8) << 4) |
+            (self.nsapi & 0x0f)];
+        if self.first_segment {
+            data.push(((self.dcomp & 0x0f) << 4) | (self.pcomp & 0x0f));
+        }
+        match self.pdu_type {
+            SndcpPduType::Unitdata => {
+                data.push(((self.segment_number & 0x0f) << 4) | ((self.npdu_number >> 8) & 0x0f) as u8);
+                data.push(self.npdu_number as u8);
+            },
+            SndcpPduType::Data if self.first_segment => data.push(self.npdu_number as u8),
+            SndcpPduType::Data => {}
+        }
+        data.extend_from_slice(&self.data);
+        data
+    }
+}
+
+/// Reassembled N-PDU (usually an IP packet).
+#[derive(Clone, PartialEq, Debug)]
+pub struct NPdu {
+    pub pdu_type: SndcpPduType,
+    pub nsapi: u8,
+    pub npdu_number: u16,
+    pub dcomp: u8,
+    pub pcomp: u8,
+    pub data: Vec<u8>
+}
+
+impl NPdu {
+    /// Whether the N-PDU data or headers are compressed (with the
+    /// algorithms negotiated through SNDCP XID, which are not decoded
+    /// here), in which case the data is not a plain network layer packet.
+    pub fn is_compressed(&self) -> bool {
+        self.dcomp != 0 || self.pcomp != 0
+    }
+}
+
+/// Segments received for an N-PDU.
+struct PendingNPdu {
+    first_segment: Option<SndcpPdu>,
+    segments: BTreeMap<u8, Vec<u8>>,
+    last_segment_number: Option<u8>
+}
+
+/// Reassembly of the N-PDUs sent on the NSAPIs of an MS, from its
+/// SNDCP PDUs. Segments of acknowledged N-PDUs are expected in order,
+/// as LLC delivers them, while those of unacknowledged N-PDUs may be
+/// reordered (but incomplete N-PDUs are dropped as soon as a segment of
+/// a following N-PDU is received on their NSAPI).
+#[derive(Default)]
+pub struct SndcpReassembler {
+    pending: HashMap<(SndcpPduType, u8), (u16, PendingNPdu)>
+}
+
+impl SndcpReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Process the information field of a deciphered LLC frame,
+    /// returning the N-PDU it completes, if any.
+    pub fn push(&mut self, llc_information: &[u8]) -> Result<Option<NPdu>, SndcpError> {
+        self.push_pdu(SndcpPdu::parse(llc_information)?)
+    }
+    
+    /// Process a parsed PDU, returning the N-PDU it completes, if any.
+    pub fn push_pdu(&mut self, pdu: SndcpPdu) -> Result<Option<NPdu>, SndcpError> {
+        let key = (pdu.pdu_type, pdu.nsapi);
+        
+        // Segments of acknowledged N-PDUs are numbered by their order,
+        // and only carry the N-PDU number in the first one
+        let (npdu_number, segment_number) = match pdu.pdu_type {
+            SndcpPduType::Unitdata => (pdu.npdu_number, pdu.segment_number),
+            SndcpPduType::Data if pdu.first_segment => (pdu.npdu_number, 0),
+            SndcpPduType::Data => match self.pending.get(&key) {
+                Some((npdu_number, pending)) => (*npdu_number, pending.segments.len() as u8),
+                None => return Err(SndcpError::MissingFirstSegment)
+            }
+        };
+        
+        let restart = match self.pending.get(&key) {
+            Some((pending_number, _)) => *pending_number != npdu_number ||
+                (pdu.pdu_type == SndcpPduType::Data && pdu.first_segment),
+            None => true
+        };
+        if restart {
+            self.pending.insert(key, (npdu_number, PendingNPdu {
+                first_segment: None,
+                segments: BTreeMap::new(),
+                last_segment_number: None
+            }));
+        }
+        
+        let (_, pending) = self.pending.get_mut(&key).unwrap();
+        if !pdu.more_segments {
+            pending.last_segment_number = Some(segment_number);
+        }
+        pending.segments.insert(segment_number, pdu.data.clone());
+        if pdu.first_segment {
+            pending.first_segment = Some(pdu);
+        }
+        
+        let complete = match (&pending.first_segment, pending.last_segment_number) {
+            (Some(_), Some(last_segment_number)) => pending.segments.len() == last_segment_number as usize + 1,
+            _ => false
+        };
+        if !complete {
+            return Ok(None);
+        }
+        
+        let (_, pending) = self.pending.remove(&key).unwrap();
+        let first_segment = pending.first_segment.unwrap();
+        Ok(Some(NPdu {
+            pdu_type: first_segment.pdu_type,
+            nsapi: first_segment.nsapi,
+            npdu_number,
+            dcomp: first_segment.dcomp,
+            pcomp: first_segment.pcomp,
+            data: pending.segments.into_values().flatten().collect()
+        }))
+    }
+}
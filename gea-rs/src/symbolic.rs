@@ -0,0 +1,416 @@
+//-----------------------------------------------------------------------------/
+// Software Name : gea12
+// Version : 0.1
+//
+// Copyright 2021. Marin Moulinier. P1Sec.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+// 
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+//--------------------------------------------------------
+// File Name : symbolic.rs
+// Created : 2026-10-18
+// Authors : Marin Moulinier
+//--------------------------------------------------------
+//  
+//  Symbolic version of GEA-1 and GEA-2, whose registers hold polynomials
+//  over GF(2) instead of bits, for algebraic and cube attacks
+//-----------------------------------------------------------------------------/
+
+
+use std::fmt;
+
+use crate::registers::s_register::SRegister;
+use crate::registers::a_register::ARegister;
+use crate::registers::b_register::BRegister;
+use crate::registers::c_register::CRegister;
+use crate::registers::d_register::DRegister;
+use crate::registers::w_register::WRegister;
+use crate::link_direction::LinkDirection;
+use crate::boolean_function::BooleanFunction;
+use crate::gea_algorithm::LfsrAlgorithm;
+
+/// Polynomial over GF(2) in algebraic normal form, of up to 128
+/// variables.
+///
+/// Each monomial is represented by the bit mask of its variables (the
+/// empty mask being the constant monomial 1), and monomials are kept
+/// sorted.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Polynomial {
+    monomials: Vec<u128>
+}
+
+impl Polynomial {
+    pub fn zero() -> Self {
+        Self { monomials: vec![] }
+    }
+    
+    pub fn one() -> Self {
+        Self { monomials: vec![0] }
+    }
+    
+    pub fn constant(value: bool) -> Self {
+        if value { Self::one() } else { Self::zero() }
+    }
+    
+    pub fn variable(var_pos: usize) -> Self {
+        Self { monomials: vec![1 << var_pos] }
+    }
+    
+    /// Monomials of the polynomial, as bit masks of variables.
+    pub fn monomials(&self) -> &[u128] {
+        &self.monomials
+    }
+    
+    pub fn is_zero(&self) -> bool {
+        self.monomials.is_empty()
+    }
+    
+    pub fn num_monomials(&self) -> usize {
+        self.monomials.len()
+    }
+    
+    /// Degree of the largest monomial (0 for a constant).
+    pub fn degree(&self) -> usize {
+        self.monomials.iter().map(|monomial| monomial.count_ones() as usize).max().unwrap_or(0)
+    }
+    
+    /// Sum of two polynomials.
+    pub fn xor(&self, other: &Polynomial) -> Polynomial {
+        let mut monomials = Vec::with_capacity(self.monomials.len() + other.monomials.len());
+        let (mut left, mut right) = (self.monomials.iter().peekable(), other.monomials.iter().peekable());
+        loop {
+            match (left.peek(), right.peek()) {
+                (Some(&&left_monomial), Some(&&right_monomial)) => {
+                    if left_monomial < right_monomial {
+                        monomials.push(left_monomial);
+                        left.next();
+                    }
+                    else if right_monomial < left_monomial {
+                        monomials.push(right_monomial);
+                        right.next();
+                    }
+                    else {
+                        left.next();
+                        right.next();
+                    }
+                },
+                (Some(_), None) => monomials.extend(left.by_ref()),
+                (None, Some(_)) => monomials.extend(right.by_ref()),
+                (None, None) => break
+            }
+        }
+        Polynomial { monomials }
+    }
+    
+    /// Product of two polynomials.
+    pub fn and(&self, other: &Polynomial) -> Polynomial {
+        let mut products = Vec::with_capacity(self.monomials.len() * other.monomials.len());
+        for left_monomial in &self.monomials {
+            for right_monomial in &other.monomials {
+                products.push(left_monomial | right_monomial);
+            }
+        }
+        products.sort_unstable();
+        
+        // Monomials appearing an even number of times cancel out
+        let mut monomials: Vec<u128> = Vec::with_capacity(products.len());
+        for monomial in products {
+            if monomials.last() == Some(&monomial) {
+                monomials.pop();
+            }
+            else {
+                monomials.push(monomial);
+            }
+        }
+        Polynomial { monomials }
+    }
+    
+    /// Evaluate the polynomial, the n-th bit of "assignment" being the
+    /// value of the n-th variable.
+    pub fn evaluate(&self, assignment: u128) -> bool {
+        self.monomials.iter()
+            .filter(|&&monomial| monomial & !assignment == 0)
+            .count() & 1 != 0
+    }
+    
+    /// Substitute the given variables by constants, the n-th bit of
+    /// "values" being the value of the n-th variable when the n-th bit
+    /// of "variables" is set.
+    pub fn substitute(&self, variables: u128, values: u128) -> Polynomial {
+        let mut monomials: Vec<u128> = self.monomials.iter()
+            .filter(|&&monomial| monomial & variables & !values == 0)
+            .map(|&monomial| monomial & !variables)
+            .collect();
+        monomials.sort_unstable();
+        let mut reduced: Vec<u128> = Vec::with_capacity(monomials.len());
+        for monomial in monomials {
+            if reduced.last() == Some(&monomial) {
+                reduced.pop();
+            }
+            else {
+                reduced.push(monomial);
+            }
+        }
+        Polynomial { monomials: reduced }
+    }
+    
+    /// Format the polynomial, e.g. "k0*k12 ^ v3 ^ 1", using the given
+    /// variable names.
+    pub fn format_with(&self, variable_names: &[String]) -> String {
+        if self.monomials.is_empty() {
+            return "0".to_string();
+        }
+        let mut monomials: Vec<Vec<usize>> = self.monomials.iter()
+            .map(|monomial| (0..128).filter(|var_pos| (monomial >> var_pos) & 1 != 0).collect())
+            .collect();
+        monomials.sort_by(|left, right| right.len().cmp(&left.len()).then(left.cmp(right)));
+        monomials.iter()
+            .map(|variables| {
+                if variables.is_empty() {
+                    "1".to_string()
+                }
+                else {
+                    variables.iter()
+                        .map(|&var_pos| variable_names.get(var_pos).cloned()
+                            .unwrap_or_else(|| format!("x{}", var_pos)))
+                        .collect::<Vec<String>>()
+                        .join("*")
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ^ ")
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.format_with(&[]))
+    }
+}
+
+/// Symbolic register, whose n-th bit is the n-th polynomial.
+struct SymbolicRegister {
+    bits: Vec<Polynomial>,
+    taps: u64,
+    f_inputs: [usize; 7]
+}
+
+impl SymbolicRegister {
+    fn new(size: usize, taps: u64, f_inputs: [usize; 7]) -> Self {
+        Self { bits: vec![Polynomial::zero(); size], taps, f_inputs }
+    }
+    
+    /// Clock a keystream generation register (A, B, C or D).
+    fn clock(&mut self, input: &Polynomial) {
+        let feedback = self.bits.remove(0).xor(input);
+        for (bit_pos, bit) in self.bits.iter_mut().enumerate() {
+            if (self.taps >> bit_pos) & 1 != 0 {
+                *bit = bit.xor(&feedback);
+            }
+        }
+        self.bits.push(feedback);
+    }
+    
+    /// Clock an initialization register (S or W), whose feedback
+    /// goes through the "f" function.
+    fn clock_nonlinear(&mut self, input: &Polynomial, f_anf: &[bool]) {
+        let f_output = self.f_function(f_anf);
+        let feedback = self.bits.remove(0).xor(&f_output).xor(input);
+        self.bits.push(feedback);
+    }
+    
+    fn f_function(&self, f_anf: &[bool]) -> Polynomial {
+        let inputs: Vec<&Polynomial> = self.f_inputs.iter().map(|&bit_pos| &self.bits[bit_pos]).collect();
+        
+        // Products of the inputs, for each subset of inputs
+        let mut products: Vec<Option<Polynomial>> = vec![None; f_anf.len()];
+        products[0] = Some(Polynomial::one());
+        let mut output = Polynomial::zero();
+        for monomial in 1..f_anf.len() {
+            if !f_anf[monomial] {
+                continue;
+            }
+            let mut subset = 0;
+            for (var_pos, input) in inputs.iter().enumerate() {
+                if (monomial >> var_pos) & 1 != 0 {
+                    let next_subset = subset | (1 << var_pos);
+                    if products[next_subset].is_none() {
+                        products[next_subset] = Some(products[subset].as_ref().unwrap().and(input));
+                    }
+                    subset = next_subset;
+                }
+            }
+            output = output.xor(products[monomial].as_ref().unwrap());
+        }
+        output
+    }
+}
+
+/// Symbolic GEA-1 or GEA-2 state: each bit of the registers is held as
+/// a polynomial over the chosen variables.
+///
+/// As in the concrete implementation, the S (or W) register is loaded
+/// with the IV, direction bit and key, mixed, then used for loading the
+/// A, B, C (and D) registers. The replacement of a zero register by
+/// one at this step is not represented, as it happens with a negligible
+/// probability.
+pub struct SymbolicState {
+    pub algorithm: LfsrAlgorithm,
+    
+    /// Names of the variables, used for formatting polynomials.
+    pub variable_names: Vec<String>,
+    
+    /// Polynomials held by the S or W register once initialized.
+    pub init_register: Vec<Polynomial>,
+    
+    registers: Vec<SymbolicRegister>
+}
+
+impl SymbolicState {
+    /// Create a symbolic state whose variables are the key bits k0 to k63
+    /// (the n-th variable being the n-th key bit), with a fixed IV and
+    /// direction bit, and a given number of mixing clocks (the actual
+    /// algorithms using `SRegister::MIXING_CLOCKS` or
+    /// `WRegister::MIXING_CLOCKS`).
+    pub fn from_key(algorithm: LfsrAlgorithm, iv: u32, direction: LinkDirection, mixing_clocks: usize) -> Self {
+        Self::initialize(
+            algorithm,
+            (0..64).map(Polynomial::variable).collect(),
+            (0..32).map(|bit_pos| Polynomial::constant((iv >> bit_pos) & 1 != 0)).collect(),
+            Polynomial::constant(matches!(direction, LinkDirection::Downlink)),
+            mixing_clocks,
+            (0..64).map(|bit_pos| format!("k{}", bit_pos)).collect()
+        )
+    }
+    
+    /// Create a symbolic state whose variables are the key bits k0 to k63,
+    /// then the IV bits v0 to v31 (being variables 64 to 95).
+    pub fn from_key_and_iv(algorithm: LfsrAlgorithm, direction: LinkDirection, mixing_clocks: usize) -> Self {
+        Self::initialize(
+            algorithm,
+            (0..64).map(Polynomial::variable).collect(),
+            (64..96).map(Polynomial::variable).collect(),
+            Polynomial::constant(matches!(direction, LinkDirection::Downlink)),
+            mixing_clocks,
+            (0..64).map(|bit_pos| format!("k{}", bit_pos))
+                .chain((0..32).map(|bit_pos| format!("v{}", bit_pos)))
+                .collect()
+        )
+    }
+    
+    /// Create a symbolic state whose variables are the bits of the S (or W)
+    /// register, once initialized: s0 to s63 (or w0 to w96).
+    ///
+    /// Keystream bits are then polynomials of degree 4, but with a large
+    /// number of monomials (around 300000 for GEA-1), as each register
+    /// bit is a linear combination of many initialization register bits.
+    pub fn from_init_register(algorithm: LfsrAlgorithm) -> Self {
+        let (size, prefix) = match algorithm {
+            LfsrAlgorithm::GEA1 => (SRegister::SIZE, "s"),
+            LfsrAlgorithm::GEA2 => (WRegister::SIZE, "w"),
+        };
+        Self::with_init_register(
+            algorithm,
+            (0..size).map(Polynomial::variable).collect(),
+            (0..size).map(|bit_pos| format!("{}{}", prefix, bit_pos)).collect()
+        )
+    }
+    
+    /// Create a symbolic state from arbitrary polynomials for the bits of
+    /// the initialized S (or W) register, e.g. when only a part of them
+    /// is unknown.
+    pub fn with_init_register(algorithm: LfsrAlgorithm, init_register: Vec<Polynomial>,
+            variable_names: Vec<String>) -> Self {
+        let mut state = Self {
+            algorithm,
+            variable_names,
+            init_register,
+            registers: vec![]
+        };
+        state.load_registers();
+        state
+    }
+    
+    /// Create a symbolic state from arbitrary polynomials for the key,
+    /// IV and direction bits.
+    ///
+    /// Polynomials grow very quickly through the initialization, so that
+    /// only a small number of variables (a dozen or so) can be tracked
+    /// through the full number of mixing clocks.
+    pub fn initialize(algorithm: LfsrAlgorithm, key: Vec<Polynomial>, iv: Vec<Polynomial>,
+            direction: Polynomial, mixing_clocks: usize, variable_names: Vec<String>) -> Self {
+        let f_anf = BooleanFunction::gea_f_function().algebraic_normal_form();
+        let mut init_register = match algorithm {
+            LfsrAlgorithm::GEA1 => SymbolicRegister::new(SRegister::SIZE, 0, SRegister::F_INPUTS),
+            LfsrAlgorithm::GEA2 => SymbolicRegister::new(WRegister::SIZE, 0, WRegister::F_INPUTS),
+        };
+        for input in iv.iter().chain(std::iter::once(&direction)).chain(key.iter()) {
+            init_register.clock_nonlinear(input, &f_anf);
+        }
+        for _num_clock in 0..mixing_clocks {
+            init_register.clock_nonlinear(&Polynomial::zero(), &f_anf);
+        }
+        
+        Self::with_init_register(algorithm, init_register.bits, variable_names)
+    }
+    
+    /// Load the A, B, C (and D) registers from the S (or W) register, in
+    /// the same way as `GEA1State::initialize` and `GEA2State::initialize`.
+    fn load_registers(&mut self) {
+        let mut registers = vec![
+            SymbolicRegister::new(ARegister::SIZE, ARegister::TAPS, ARegister::F_INPUTS),
+            SymbolicRegister::new(BRegister::SIZE, BRegister::TAPS, BRegister::F_INPUTS),
+            SymbolicRegister::new(CRegister::SIZE, CRegister::TAPS, CRegister::F_INPUTS)
+        ];
+        let rotations: &[usize] = match self.algorithm {
+            LfsrAlgorithm::GEA1 => &[0, 16, 32],
+            LfsrAlgorithm::GEA2 => {
+                registers.push(SymbolicRegister::new(DRegister::SIZE, DRegister::TAPS, DRegister::F_INPUTS));
+                &[16, 33, 51, 0]
+            },
+        };
+        
+        let init_size = self.init_register.len();
+        for (register, rotation) in registers.iter_mut().zip(rotations) {
+            for input_pos in 0..init_size {
+                register.clock(&self.init_register[(input_pos + rotation) % init_size]);
+            }
+        }
+        self.registers = registers;
+    }
+    
+    /// Compute the polynomial of the next keystream bit, then clock
+    /// the registers.
+    pub fn next_keystream_bit(&mut self) -> Polynomial {
+        let f_anf = BooleanFunction::gea_f_function().algebraic_normal_form();
+        let mut output = Polynomial::zero();
+        for register in self.registers.iter_mut() {
+            output = output.xor(&register.f_function(&f_anf));
+            register.clock(&Polynomial::zero());
+        }
+        output
+    }
+    
+    /// Compute the polynomials of the next keystream bits, in the order
+    /// they are output (lowest bit of each keystream byte first).
+    pub fn generate_bits(&mut self, num_bits: usize) -> Vec<Polynomial> {
+        (0..num_bits).map(|_| self.next_keystream_bit()).collect()
+    }
+    
+    /// Format a polynomial using the names of the variables of the state.
+    pub fn format_polynomial(&self, polynomial: &Polynomial) -> String {
+        polynomial.format_with(&self.variable_names)
+    }
+}